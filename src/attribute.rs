@@ -151,6 +151,22 @@ where
     // }
 }
 
+pub trait IntoAttribute {
+    fn into_attribute(self) -> Attribute;
+}
+
+impl IntoAttribute for Meta1 {
+    fn into_attribute(self) -> Attribute {
+        parse_quote!( #[ #self ] )
+    }
+}
+
+impl IntoAttribute for MetaList1 {
+    fn into_attribute(self) -> Attribute {
+        Meta1::List(self).into_attribute()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -234,19 +250,3 @@ mod test {
         assert_eq!(doc, "doc line 1\ndoc line 2");
     }
 }
-
-pub trait IntoAttribute {
-    fn into_attribute(self) -> Attribute;
-}
-
-impl IntoAttribute for Meta1 {
-    fn into_attribute(self) -> Attribute {
-        parse_quote!( #[ #self ] )
-    }
-}
-
-impl IntoAttribute for MetaList1 {
-    fn into_attribute(self) -> Attribute {
-        Meta1::List(self).into_attribute()
-    }
-}
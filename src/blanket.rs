@@ -0,0 +1,341 @@
+use crate::delegate::{forward_block, is_self_ty};
+use crate::error::combine;
+use crate::item::{FunctionLike, ItemLike};
+use crate::skeleton::{arg_ident, impl_const, impl_fn, impl_header, impl_type, named_sig};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse_quote, Error, Expr, FnArg, GenericArgument, Generics, Ident, ImplItem, ImplItemFn,
+    ItemImpl, ItemTrait, PathArguments, Receiver, Result, ReturnType, Type,
+};
+
+/// Wrapper types which can forward a trait to their pointee
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlanketPtr {
+    /// `&T`
+    Ref,
+    /// `&mut T`
+    RefMut,
+    /// `Box<T>`
+    Box,
+    /// `Rc<T>`
+    Rc,
+    /// `Arc<T>`
+    Arc,
+}
+
+/// Returns `true` for `Box<Self>`
+fn is_box_self(ty: &Type) -> bool {
+    let path = match ty {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => return false,
+    };
+    let segment = match path.segments.last() {
+        Some(segment) if segment.ident == "Box" => segment,
+        _ => return false,
+    };
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => matches!(
+            &args.args[0],
+            GenericArgument::Type(Type::Path(ty)) if ty.qself.is_none() && ty.path.is_ident("Self")
+        ),
+        _ => false,
+    }
+}
+
+impl BlanketPtr {
+    pub const ALL: [BlanketPtr; 5] = [
+        BlanketPtr::Ref,
+        BlanketPtr::RefMut,
+        BlanketPtr::Box,
+        BlanketPtr::Rc,
+        BlanketPtr::Arc,
+    ];
+
+    /// Returns the wrapper as written in diagnostics, e.g. `Box<T>`
+    pub fn name(&self) -> &'static str {
+        match self {
+            BlanketPtr::Ref => "&T",
+            BlanketPtr::RefMut => "&mut T",
+            BlanketPtr::Box => "Box<T>",
+            BlanketPtr::Rc => "Rc<T>",
+            BlanketPtr::Arc => "Arc<T>",
+        }
+    }
+
+    /// Returns the wrapper type of `param`
+    pub fn ty(&self, param: &Ident) -> Type {
+        match self {
+            BlanketPtr::Ref => parse_quote!(&#param),
+            BlanketPtr::RefMut => parse_quote!(&mut #param),
+            BlanketPtr::Box => parse_quote!(::std::boxed::Box<#param>),
+            BlanketPtr::Rc => parse_quote!(::std::rc::Rc<#param>),
+            BlanketPtr::Arc => parse_quote!(::std::sync::Arc<#param>),
+        }
+    }
+
+    /// Returns the argument passed as `receiver` to the pointee's method
+    fn forward_receiver(&self, receiver: &Receiver) -> std::result::Result<Expr, &'static str> {
+        if receiver.colon_token.is_some() {
+            return match self {
+                BlanketPtr::Box if is_box_self(&receiver.ty) => Ok(parse_quote!(*self)),
+                _ => Err("of its receiver type"),
+            };
+        }
+        match (&receiver.reference, &receiver.mutability, self) {
+            (Some(_), None, _) => Ok(parse_quote!(&**self)),
+            (Some(_), Some(_), BlanketPtr::RefMut | BlanketPtr::Box) => {
+                Ok(parse_quote!(&mut **self))
+            }
+            (Some(_), Some(_), _) => Err("it takes `&mut self`"),
+            (None, _, BlanketPtr::Box) => Ok(parse_quote!(*self)),
+            (None, _, _) => Err("it takes `self` by value"),
+        }
+    }
+
+    /// Generates a method of `item` forwarding to the pointee `param`
+    pub fn forward_fn(
+        &self,
+        item: &ItemTrait,
+        param: &Ident,
+        f: &dyn FunctionLike,
+    ) -> Result<ImplItemFn> {
+        let sig = named_sig(f.sig());
+        let name = sig.ident.clone();
+        let error = |tokens: &dyn ToTokens, reason: &str| {
+            Error::new_spanned(
+                tokens,
+                format!(
+                    "`{}` can't implement `{}` because {}",
+                    self.name(),
+                    name,
+                    reason
+                ),
+            )
+        };
+        if let ReturnType::Type(_, ty) = &sig.output {
+            if is_self_ty(ty) {
+                return Err(error(ty, "it returns `Self`"));
+            }
+        }
+        let mut args: Vec<Expr> = Vec::new();
+        for input in &sig.inputs {
+            match input {
+                FnArg::Receiver(receiver) => {
+                    let arg = self
+                        .forward_receiver(receiver)
+                        .map_err(|reason| error(receiver, reason))?;
+                    args.push(arg);
+                }
+                FnArg::Typed(arg) if is_self_ty(&arg.ty) => {
+                    return Err(error(arg, "it takes `Self` as a parameter"));
+                }
+                FnArg::Typed(arg) => {
+                    let ident = arg_ident(arg);
+                    args.push(parse_quote!(#ident));
+                }
+            }
+        }
+        let trait_path = trait_path(item);
+        let block = forward_block(&sig, &quote!(<#param as #trait_path>), &args);
+        let mut forwarded = impl_fn(f, block);
+        forwarded.sig = sig;
+        Ok(forwarded)
+    }
+
+    /// Generates `impl<T: Trait + ?Sized> Trait for Wrapper<T>`.
+    ///
+    /// `Err` with every required method the wrapper can't forward; such provided methods keep
+    /// their default bodies. `?Sized` is dropped when a method
+    /// of `Box<T>` takes `self` by value. Supertraits are expected to be implemented separately.
+    pub fn build(&self, item: &ItemTrait) -> Result<ItemImpl> {
+        let param = fresh_param(item);
+        let trait_path = trait_path(item);
+        let mut fns = Vec::new();
+        let mut errors: Vec<Error> = Vec::new();
+        for f in item
+            .items
+            .iter()
+            .filter_map(|item| item.function_or_method().ok())
+        {
+            match self.forward_fn(item, &param, f) {
+                Ok(f) => fns.push(Some(f)),
+                // provided methods which can't be forwarded keep their default body
+                Err(_) if f.block().is_some() => fns.push(None),
+                Err(e) => errors.push(e),
+            }
+        }
+        combine(errors)?;
+
+        let by_value = fns.iter().flatten().any(|f| {
+            matches!(
+                f.sig.receiver(),
+                Some(receiver) if receiver.reference.is_none() && receiver.colon_token.is_none()
+            )
+        });
+        let generics: Generics = if by_value {
+            parse_quote!(<#param: #trait_path>)
+        } else {
+            parse_quote!(<#param: #trait_path + ?Sized>)
+        };
+        let mut generated = impl_header(item, self.ty(&param), &generics, None)?;
+        let mut fns = fns.into_iter();
+        for trait_item in &item.items {
+            if let Ok(c) = trait_item.constant() {
+                let ident = c.ident();
+                let expr = parse_quote!(<#param as #trait_path>::#ident);
+                generated.items.push(ImplItem::Const(impl_const(c, expr)));
+            } else if let Ok(t) = trait_item.type_alias() {
+                let ident = t.ident();
+                let (_, ty_generics, _) = t.generics().split_for_impl();
+                let ty = parse_quote!(<#param as #trait_path>::#ident #ty_generics);
+                generated.items.push(ImplItem::Type(impl_type(t, ty)));
+            } else if trait_item.function_or_method().is_ok() {
+                generated
+                    .items
+                    .extend(fns.next().flatten().map(ImplItem::Fn));
+            }
+        }
+        Ok(generated)
+    }
+
+    /// Generates impls for every wrapper in `ptrs`. `Err` combines the errors of all of them.
+    pub fn build_all(ptrs: &[BlanketPtr], item: &ItemTrait) -> Result<Vec<ItemImpl>> {
+        let mut impls = Vec::new();
+        let mut errors = Vec::new();
+        for ptr in ptrs {
+            match ptr.build(item) {
+                Ok(generated) => impls.push(generated),
+                Err(e) => errors.push(e),
+            }
+        }
+        combine(errors)?;
+        Ok(impls)
+    }
+}
+
+/// Returns `Trait<..>` of `item`
+fn trait_path(item: &ItemTrait) -> syn::Path {
+    let ident = &item.ident;
+    let (_, ty_generics, _) = item.generics.split_for_impl();
+    parse_quote!(#ident #ty_generics)
+}
+
+/// Returns `T`, or `T1`, `T2`.. if the trait already has a parameter of the name
+fn fresh_param(item: &ItemTrait) -> Ident {
+    let taken = |ident: &Ident| {
+        item.generics
+            .type_params()
+            .any(|param| param.ident == *ident)
+    };
+    let mut param = format_ident!("T");
+    let mut i = 0usize;
+    while taken(&param) {
+        i += 1;
+        param = format_ident!("T{}", i);
+    }
+    param
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_quote_eq;
+
+    #[test]
+    fn test_build() {
+        let item: ItemTrait = parse_quote! {
+            trait Store<T> {
+                type Iter<'a>: Iterator where Self: 'a;
+                const CAPACITY: usize;
+                fn get(&self, key: &T) -> Option<&str>;
+                fn set(&mut self, key: T, (a, b): (u8, u8));
+                fn count(_: u8) -> usize { 0 }
+                fn dup(&self) -> Option<Self> where Self: Sized { None }
+            }
+        };
+        let generated = BlanketPtr::RefMut.build(&item).unwrap();
+        let expected: ItemImpl = parse_quote! {
+            impl<T, T1: Store<T> + ?Sized> Store<T> for &mut T1 {
+                type Iter<'a> = <T1 as Store<T> >::Iter<'a> where Self: 'a;
+                const CAPACITY: usize = <T1 as Store<T> >::CAPACITY;
+                fn get(&self, key: &T) -> Option<&str> {
+                    <T1 as Store<T> >::get(&**self, key)
+                }
+                fn set(&mut self, key: T, arg2: (u8, u8)) {
+                    <T1 as Store<T> >::set(&mut **self, key, arg2)
+                }
+                fn count(arg0: u8) -> usize {
+                    <T1 as Store<T> >::count(arg0)
+                }
+            }
+        };
+        assert_quote_eq!(generated, expected);
+
+        let item: ItemTrait = parse_quote! {
+            trait Run {
+                fn run(self);
+                fn run_boxed(self: Box<Self>);
+            }
+        };
+        let generated = BlanketPtr::Box.build(&item).unwrap();
+        let expected: ItemImpl = parse_quote! {
+            impl<T: Run> Run for ::std::boxed::Box<T> {
+                fn run(self) {
+                    <T as Run>::run(*self)
+                }
+                fn run_boxed(self: Box<Self>) {
+                    <T as Run>::run_boxed(*self)
+                }
+            }
+        };
+        assert_quote_eq!(generated, expected);
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let item: ItemTrait = parse_quote! {
+            trait A {
+                fn get(&self);
+                fn set(&mut self);
+                fn into_inner(self) -> u8;
+                fn new() -> Self;
+                fn dup(&self) -> Option<Self>;
+                fn extend(&self, other: Vec<Self>);
+            }
+        };
+        assert!(BlanketPtr::Ref.build(&item).is_err());
+        let err = BlanketPtr::build_all(&[BlanketPtr::Ref, BlanketPtr::Rc], &item)
+            .err()
+            .unwrap();
+        let messages: Vec<_> = err.into_iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "`&T` can't implement `set` because it takes `&mut self`",
+                "`&T` can't implement `into_inner` because it takes `self` by value",
+                "`&T` can't implement `new` because it returns `Self`",
+                "`&T` can't implement `dup` because it returns `Self`",
+                "`&T` can't implement `extend` because it takes `Self` as a parameter",
+                "`Rc<T>` can't implement `set` because it takes `&mut self`",
+                "`Rc<T>` can't implement `into_inner` because it takes `self` by value",
+                "`Rc<T>` can't implement `new` because it returns `Self`",
+                "`Rc<T>` can't implement `dup` because it returns `Self`",
+                "`Rc<T>` can't implement `extend` because it takes `Self` as a parameter",
+            ]
+        );
+
+        let item: ItemTrait = parse_quote! {
+            trait B {
+                type Item;
+                fn get(&self) -> u8;
+                fn item(&self, item: Self::Item) -> <Self as B>::Item;
+            }
+        };
+        assert_eq!(
+            BlanketPtr::build_all(&BlanketPtr::ALL, &item)
+                .unwrap()
+                .len(),
+            5
+        );
+    }
+}
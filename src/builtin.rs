@@ -0,0 +1,431 @@
+use crate::attribute::{AttributeExt, IntoAttribute};
+#[cfg(feature = "full")]
+use crate::item::ItemLike;
+use crate::meta::{Meta1, NestedMeta};
+use syn::{parse_quote, Attribute, Error, Expr, ExprLit, Lit, LitStr, Result};
+
+/// `#[deprecated]`, `#[deprecated = "note"]` or `#[deprecated(since = "..", note = "..")]`
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+pub struct Deprecated {
+    pub since: Option<LitStr>,
+    pub note: Option<LitStr>,
+}
+
+/// `#[inline]`, `#[inline(always)]` or `#[inline(never)]`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Inline {
+    Hint,
+    Always,
+    Never,
+}
+
+/// Typed model of well-known built-in attributes
+#[derive(Clone)]
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+pub enum BuiltinAttr {
+    Deprecated(Deprecated),
+    /// `#[must_use]` or `#[must_use = "reason"]`
+    MustUse(Option<LitStr>),
+    Inline(Inline),
+    NonExhaustive,
+    TrackCaller,
+    Cold,
+    ExportName(LitStr),
+    LinkName(LitStr),
+    NoMangle,
+    Path(LitStr),
+    /// `#[macro_export]` or `#[macro_export(local_inner_macros)]`
+    MacroExport {
+        local_inner_macros: bool,
+    },
+}
+
+/// Names of attributes modeled by [BuiltinAttr]
+const BUILTIN_NAMES: &[&str] = &[
+    "deprecated",
+    "must_use",
+    "inline",
+    "non_exhaustive",
+    "track_caller",
+    "cold",
+    "export_name",
+    "link_name",
+    "no_mangle",
+    "path",
+    "macro_export",
+];
+
+pub(crate) fn lit_str(expr: &Expr) -> Result<LitStr> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => Ok(lit.clone()),
+        other => Err(Error::new_spanned(other, "expected string literal")),
+    }
+}
+
+fn malformed(meta: &Meta1, name: &str) -> Error {
+    Error::new_spanned(meta, format!("malformed `{}` attribute input", name))
+}
+
+impl Deprecated {
+    /// Parses `deprecated` meta in any of its forms
+    pub fn from_meta(meta: &Meta1) -> Result<Self> {
+        let mut deprecated = Self::default();
+        match meta {
+            Meta1::Path(_) => {}
+            Meta1::NameValue(nv) => deprecated.note = Some(lit_str(&nv.value)?),
+            Meta1::List(list) => {
+                for nested in &list.nested {
+                    let nv = match nested {
+                        NestedMeta::Meta(Meta1::NameValue(nv)) => nv,
+                        _ => return Err(Error::new_spanned(nested, "expected `since` or `note`")),
+                    };
+                    let field = if nv.path.is_ident("since") {
+                        &mut deprecated.since
+                    } else if nv.path.is_ident("note") {
+                        &mut deprecated.note
+                    } else {
+                        return Err(Error::new_spanned(&nv.path, "expected `since` or `note`"));
+                    };
+                    if field.is_some() {
+                        return Err(Error::new_spanned(&nv.path, "duplicated deprecated item"));
+                    }
+                    *field = Some(lit_str(&nv.value)?);
+                }
+            }
+        }
+        Ok(deprecated)
+    }
+
+    /// Constructs and returns the shortest form of `deprecated` meta
+    pub fn to_meta(&self) -> Meta1 {
+        match (&self.since, &self.note) {
+            (None, None) => parse_quote!(deprecated),
+            (None, Some(note)) => parse_quote!(deprecated = #note),
+            (Some(since), None) => parse_quote!(deprecated(since = #since)),
+            (Some(since), Some(note)) => parse_quote!(deprecated(since = #since, note = #note)),
+        }
+    }
+}
+
+impl BuiltinAttr {
+    /// Parses a meta as a built-in attribute.
+    ///
+    /// `Ok(None)` if the meta is not one of the known attributes;
+    /// `Err` if it is known but malformed.
+    pub fn from_meta(meta: &Meta1) -> Result<Option<Self>> {
+        let name = match meta.path().get_ident() {
+            Some(ident) => ident.to_string(),
+            None => return Ok(None),
+        };
+        let attr = match (name.as_str(), meta) {
+            ("deprecated", meta) => Self::Deprecated(Deprecated::from_meta(meta)?),
+            ("must_use", Meta1::Path(_)) => Self::MustUse(None),
+            ("must_use", Meta1::NameValue(nv)) => Self::MustUse(Some(lit_str(&nv.value)?)),
+            ("inline", Meta1::Path(_)) => Self::Inline(Inline::Hint),
+            ("inline", Meta1::List(list)) => {
+                let ident = match list.nested.first() {
+                    Some(NestedMeta::Meta(Meta1::Path(path))) if list.nested.len() == 1 => {
+                        path.get_ident()
+                    }
+                    _ => None,
+                };
+                match ident {
+                    Some(ident) if ident == "always" => Self::Inline(Inline::Always),
+                    Some(ident) if ident == "never" => Self::Inline(Inline::Never),
+                    _ => return Err(malformed(meta, &name)),
+                }
+            }
+            ("non_exhaustive", Meta1::Path(_)) => Self::NonExhaustive,
+            ("track_caller", Meta1::Path(_)) => Self::TrackCaller,
+            ("cold", Meta1::Path(_)) => Self::Cold,
+            ("no_mangle", Meta1::Path(_)) => Self::NoMangle,
+            ("export_name", Meta1::NameValue(nv)) => Self::ExportName(lit_str(&nv.value)?),
+            ("link_name", Meta1::NameValue(nv)) => Self::LinkName(lit_str(&nv.value)?),
+            ("path", Meta1::NameValue(nv)) => Self::Path(lit_str(&nv.value)?),
+            ("macro_export", Meta1::Path(_)) => Self::MacroExport {
+                local_inner_macros: false,
+            },
+            ("macro_export", Meta1::List(list)) => match list.nested.first() {
+                Some(NestedMeta::Meta(Meta1::Path(path)))
+                    if list.nested.len() == 1 && path.is_ident("local_inner_macros") =>
+                {
+                    Self::MacroExport {
+                        local_inner_macros: true,
+                    }
+                }
+                _ => return Err(malformed(meta, &name)),
+            },
+            (name, _) if BUILTIN_NAMES.contains(&name) => return Err(malformed(meta, name)),
+            _ => return Ok(None),
+        };
+        Ok(Some(attr))
+    }
+
+    /// Parses an attribute as a built-in attribute. See [BuiltinAttr::from_meta]
+    ///
+    /// Attributes of other names are not parsed, so their tokens may be arbitrary.
+    pub fn from_attribute(attr: &Attribute) -> Result<Option<Self>> {
+        let known = attr
+            .path()
+            .get_ident()
+            .is_some_and(|ident| BUILTIN_NAMES.iter().any(|name| ident == name));
+        if !known {
+            return Ok(None);
+        }
+        Self::from_meta(&attr.parse_meta()?)
+    }
+
+    /// Constructs and returns the meta of the attribute
+    pub fn to_meta(&self) -> Meta1 {
+        match self {
+            Self::Deprecated(deprecated) => deprecated.to_meta(),
+            Self::MustUse(None) => parse_quote!(must_use),
+            Self::MustUse(Some(reason)) => parse_quote!(must_use = #reason),
+            Self::Inline(Inline::Hint) => parse_quote!(inline),
+            Self::Inline(Inline::Always) => parse_quote!(inline(always)),
+            Self::Inline(Inline::Never) => parse_quote!(inline(never)),
+            Self::NonExhaustive => parse_quote!(non_exhaustive),
+            Self::TrackCaller => parse_quote!(track_caller),
+            Self::Cold => parse_quote!(cold),
+            Self::ExportName(name) => parse_quote!(export_name = #name),
+            Self::LinkName(name) => parse_quote!(link_name = #name),
+            Self::NoMangle => parse_quote!(no_mangle),
+            Self::Path(path) => parse_quote!(path = #path),
+            Self::MacroExport {
+                local_inner_macros: false,
+            } => parse_quote!(macro_export),
+            Self::MacroExport {
+                local_inner_macros: true,
+            } => parse_quote!(macro_export(local_inner_macros)),
+        }
+    }
+}
+
+impl IntoAttribute for BuiltinAttr {
+    fn into_attribute(self) -> Attribute {
+        self.to_meta().into_attribute()
+    }
+}
+
+/// Accessors to built-in attributes of `syn::*Item` using `crate::ext::ItemLike`
+#[cfg(feature = "full")]
+pub trait ItemBuiltinAttrExt: ItemLike {
+    /// Returns every known built-in attribute with the original attribute to be copied
+    fn builtin_attrs(&self) -> Result<Vec<(BuiltinAttr, &Attribute)>> {
+        let mut builtins = Vec::new();
+        for attr in self.attrs()? {
+            if let Some(builtin) = BuiltinAttr::from_attribute(attr)? {
+                builtins.push((builtin, attr));
+            }
+        }
+        Ok(builtins)
+    }
+
+    /// Returns the first built-in attribute matching `f`
+    fn find_builtin_attr<F, R>(&self, f: F) -> Result<Option<R>>
+    where
+        F: FnMut(BuiltinAttr) -> Option<R>,
+    {
+        Ok(self
+            .builtin_attrs()?
+            .into_iter()
+            .map(|(builtin, _)| builtin)
+            .find_map(f))
+    }
+
+    /// Returns `#[deprecated]` of the item
+    fn deprecated(&self) -> Result<Option<Deprecated>> {
+        self.find_builtin_attr(|attr| match attr {
+            BuiltinAttr::Deprecated(deprecated) => Some(deprecated),
+            _ => None,
+        })
+    }
+    /// Returns `#[must_use]` of the item with its optional reason
+    fn must_use(&self) -> Result<Option<Option<LitStr>>> {
+        self.find_builtin_attr(|attr| match attr {
+            BuiltinAttr::MustUse(reason) => Some(reason),
+            _ => None,
+        })
+    }
+    /// Returns `#[inline]` of the item
+    fn inline(&self) -> Result<Option<Inline>> {
+        self.find_builtin_attr(|attr| match attr {
+            BuiltinAttr::Inline(inline) => Some(inline),
+            _ => None,
+        })
+    }
+    /// Returns the name of `#[export_name = ".."]`
+    fn export_name(&self) -> Result<Option<LitStr>> {
+        self.find_builtin_attr(|attr| match attr {
+            BuiltinAttr::ExportName(name) => Some(name),
+            _ => None,
+        })
+    }
+    /// Returns the name of `#[link_name = ".."]`
+    fn link_name(&self) -> Result<Option<LitStr>> {
+        self.find_builtin_attr(|attr| match attr {
+            BuiltinAttr::LinkName(name) => Some(name),
+            _ => None,
+        })
+    }
+    /// Returns the file path of `#[path = ".."]`
+    fn path_attr(&self) -> Result<Option<LitStr>> {
+        self.find_builtin_attr(|attr| match attr {
+            BuiltinAttr::Path(path) => Some(path),
+            _ => None,
+        })
+    }
+    /// Returns `#[macro_export]` of the item; `Some(true)` for `local_inner_macros`
+    fn macro_export(&self) -> Result<Option<bool>> {
+        self.find_builtin_attr(|attr| match attr {
+            BuiltinAttr::MacroExport { local_inner_macros } => Some(local_inner_macros),
+            _ => None,
+        })
+    }
+    /// Returns `true` if the item has `#[non_exhaustive]`
+    fn is_non_exhaustive(&self) -> Result<bool> {
+        self.find_builtin_attr(|attr| matches!(attr, BuiltinAttr::NonExhaustive).then_some(()))
+            .map(|found| found.is_some())
+    }
+    /// Returns `true` if the item has `#[track_caller]`
+    fn is_track_caller(&self) -> Result<bool> {
+        self.find_builtin_attr(|attr| matches!(attr, BuiltinAttr::TrackCaller).then_some(()))
+            .map(|found| found.is_some())
+    }
+    /// Returns `true` if the item has `#[cold]`
+    fn is_cold(&self) -> Result<bool> {
+        self.find_builtin_attr(|attr| matches!(attr, BuiltinAttr::Cold).then_some(()))
+            .map(|found| found.is_some())
+    }
+    /// Returns `true` if the item has `#[no_mangle]`
+    fn is_no_mangle(&self) -> Result<bool> {
+        self.find_builtin_attr(|attr| matches!(attr, BuiltinAttr::NoMangle).then_some(()))
+            .map(|found| found.is_some())
+    }
+}
+
+#[cfg(feature = "full")]
+impl ItemBuiltinAttrExt for syn::Item {}
+#[cfg(feature = "full")]
+impl ItemBuiltinAttrExt for syn::ImplItem {}
+#[cfg(feature = "full")]
+impl ItemBuiltinAttrExt for syn::TraitItem {}
+#[cfg(feature = "full")]
+impl ItemBuiltinAttrExt for syn::ForeignItem {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_quote_eq;
+
+    #[test]
+    fn test_round_trip() {
+        let metas: Vec<Meta1> = vec![
+            parse_quote!(deprecated),
+            parse_quote!(deprecated = "use g"),
+            parse_quote!(deprecated(since = "1.0", note = "use g")),
+            parse_quote!(must_use),
+            parse_quote!(must_use = "reason"),
+            parse_quote!(inline),
+            parse_quote!(inline(always)),
+            parse_quote!(inline(never)),
+            parse_quote!(non_exhaustive),
+            parse_quote!(track_caller),
+            parse_quote!(cold),
+            parse_quote!(export_name = "f"),
+            parse_quote!(link_name = "f"),
+            parse_quote!(no_mangle),
+            parse_quote!(path = "a.rs"),
+            parse_quote!(macro_export),
+            parse_quote!(macro_export(local_inner_macros)),
+        ];
+        for meta in &metas {
+            let attr = BuiltinAttr::from_meta(meta).unwrap().unwrap();
+            assert_quote_eq!(attr.to_meta(), meta);
+        }
+    }
+
+    #[test]
+    fn test_unknown_and_malformed() {
+        assert!(BuiltinAttr::from_meta(&parse_quote!(derive(Debug)))
+            .unwrap()
+            .is_none());
+        let malformed: Vec<Meta1> = vec![
+            parse_quote!(inline(sometimes)),
+            parse_quote!(cold = "yes"),
+            parse_quote!(export_name),
+            parse_quote!(deprecated(reason = "x")),
+            parse_quote!(deprecated(note = "x", note = "y")),
+        ];
+        for meta in &malformed {
+            assert!(BuiltinAttr::from_meta(meta).is_err());
+        }
+    }
+
+    #[cfg(feature = "full")]
+    #[test]
+    fn test_item_accessors() {
+        let item: syn::Item = parse_quote! {
+            #[doc = "f"]
+            #[inline(always)]
+            #[deprecated(since = "0.1")]
+            #[must_use]
+            #[track_caller]
+            fn f() {}
+        };
+        assert_eq!(item.inline().unwrap(), Some(Inline::Always));
+        let deprecated = item.deprecated().unwrap().unwrap();
+        assert_eq!(deprecated.since.unwrap().value(), "0.1");
+        assert!(deprecated.note.is_none());
+        assert!(item.must_use().unwrap().unwrap().is_none());
+        assert!(item.is_track_caller().unwrap());
+        assert!(!item.is_cold().unwrap());
+        assert!(item.export_name().unwrap().is_none());
+
+        let copied: Vec<_> = item
+            .builtin_attrs()
+            .unwrap()
+            .into_iter()
+            .filter(|(attr, _)| !matches!(attr, BuiltinAttr::TrackCaller))
+            .map(|(_, attr)| attr.clone())
+            .collect();
+        let expected = quote::quote! {
+            #[inline(always)]
+            #[deprecated(since = "0.1")]
+            #[must_use]
+        };
+        assert_quote_eq!(quote::quote!(#(#copied)*), expected);
+
+        let block: syn::ItemForeignMod = parse_quote! {
+            extern "C" {
+                #[link_name = "c_f"]
+                fn f();
+            }
+        };
+        let item = &block.items[0];
+        assert_eq!(item.link_name().unwrap().unwrap().value(), "c_f");
+        assert!(item.deprecated().unwrap().is_none());
+    }
+
+    #[cfg(feature = "full")]
+    #[test]
+    fn test_foreign_attrs() {
+        let item: syn::Item = parse_quote! {
+            #[my_attr(a + b)]
+            #[serde(with = module::path)]
+            #[inline]
+            fn f() {}
+        };
+        assert_eq!(item.inline().unwrap(), Some(Inline::Hint));
+        assert_eq!(item.builtin_attrs().unwrap().len(), 1);
+
+        let item: syn::Item = parse_quote! {
+            #[inline(a + b)]
+            fn f() {}
+        };
+        assert!(item.inline().is_err());
+    }
+}
@@ -0,0 +1,304 @@
+use crate::error::combine;
+use crate::item::{FunctionLike, ItemLike};
+use crate::object_safety::find_bare_self;
+use crate::skeleton::{
+    arg_ident, impl_const, impl_fn, impl_header, impl_type, instantiate, named_sig,
+};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    parse_quote, AngleBracketedGenericArguments, Block, Error, Expr, FnArg, GenericParam, ImplItem,
+    ImplItemFn, ItemImpl, ItemStruct, ItemTrait, Member, Path, Result, ReturnType, Signature, Type,
+};
+
+/// Returns `true` if `ty` mentions `Self` other than through a projection, like `Option<Self>`
+pub(crate) fn is_self_ty(ty: &Type) -> bool {
+    find_bare_self(ty.to_token_stream()).is_some()
+}
+
+/// Returns a block calling `qself::f(args)` for the function of `sig`.
+///
+/// Adds a turbofish for type and const parameters unless `impl Trait` is used, and `.await` for async functions.
+pub(crate) fn forward_block(sig: &Signature, qself: &TokenStream, args: &[Expr]) -> Block {
+    let ident = &sig.ident;
+    let has_impl_trait = sig
+        .inputs
+        .iter()
+        .any(|input| matches!(input, FnArg::Typed(arg) if matches!(*arg.ty, Type::ImplTrait(_))));
+    let generic_args: Vec<_> = sig
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(&param.ident),
+            GenericParam::Const(param) => Some(&param.ident),
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect();
+    let turbofish = if generic_args.is_empty() || has_impl_trait {
+        quote!()
+    } else {
+        quote!(::<#(#generic_args),*>)
+    };
+    let await_token = sig.asyncness.map(|_| quote!(.await));
+    parse_quote!({
+        #qself::#ident #turbofish(#(#args),*) #await_token
+    })
+}
+
+/// Generator of `impl Trait for Struct` forwarding every item to a field
+///
+/// Methods are called with fully qualified syntax like `<Inner as Trait>::f(&self.inner, x)`,
+/// so the field type must implement the trait; the bound is added to the where clause.
+pub struct DelegateImpl<'a> {
+    target: &'a ItemStruct,
+    member: Member,
+    field_ty: &'a Type,
+    trait_args: Option<AngleBracketedGenericArguments>,
+}
+
+impl<'a> DelegateImpl<'a> {
+    /// Delegates to `member` of `target`. `Err` if the field doesn't exist.
+    pub fn new(target: &'a ItemStruct, member: Member) -> Result<Self> {
+        let field = target
+            .fields
+            .iter()
+            .enumerate()
+            .find(|(i, field)| match &member {
+                Member::Named(ident) => field.ident.as_ref() == Some(ident),
+                Member::Unnamed(index) => index.index as usize == *i,
+            })
+            .map(|(_, field)| field);
+        match field {
+            Some(field) => Ok(Self {
+                target,
+                member,
+                field_ty: &field.ty,
+                trait_args: None,
+            }),
+            None => Err(Error::new_spanned(
+                &member,
+                format!(
+                    "no field `{}` in struct `{}`",
+                    quote!(#member),
+                    target.ident
+                ),
+            )),
+        }
+    }
+
+    /// Sets generic arguments of the trait, e.g. `<K>` for `impl<K, S> Trait<K> for A<K, S>`.
+    ///
+    /// Parameters of the trait become parameters of the impl unless given.
+    pub fn trait_args(mut self, args: AngleBracketedGenericArguments) -> Self {
+        self.trait_args = Some(args);
+        self
+    }
+
+    /// Generates a method of `trait_path` forwarding to the field
+    pub fn delegate_fn(&self, trait_path: &Path, f: &dyn FunctionLike) -> Result<ImplItemFn> {
+        let sig = named_sig(f.sig());
+        if let ReturnType::Type(_, ty) = &sig.output {
+            if is_self_ty(ty) {
+                return Err(Error::new_spanned(
+                    &sig.ident,
+                    "methods returning `Self` can't be delegated",
+                ));
+            }
+        }
+        let member = &self.member;
+        let mut args: Vec<Expr> = Vec::new();
+        for input in &sig.inputs {
+            match input {
+                FnArg::Receiver(receiver) if receiver.colon_token.is_some() => {
+                    return Err(Error::new_spanned(
+                        receiver,
+                        "typed receivers can't be delegated",
+                    ))
+                }
+                FnArg::Receiver(receiver) => {
+                    let arg = match (&receiver.reference, &receiver.mutability) {
+                        (Some(_), Some(_)) => parse_quote!(&mut self.#member),
+                        (Some(_), None) => parse_quote!(&self.#member),
+                        (None, _) => parse_quote!(self.#member),
+                    };
+                    args.push(arg);
+                }
+                FnArg::Typed(arg) if is_self_ty(&arg.ty) => {
+                    return Err(Error::new_spanned(
+                        arg,
+                        "parameters referencing `Self` can't be delegated",
+                    ))
+                }
+                FnArg::Typed(arg) => {
+                    let ident = arg_ident(arg);
+                    args.push(parse_quote!(#ident));
+                }
+            }
+        }
+        let field_ty = self.field_ty;
+        let block = forward_block(&sig, &quote!(<#field_ty as #trait_path>), &args);
+        let mut delegated = impl_fn(f, block);
+        delegated.sig = sig;
+        Ok(delegated)
+    }
+
+    /// Generates the impl of `item`.
+    ///
+    /// `Err` with every required method which can't be delegated. Such provided methods are
+    /// left to their default bodies.
+    pub fn build(&self, item: &ItemTrait) -> Result<ItemImpl> {
+        let target = self.target;
+        let ident = &target.ident;
+        let (_, ty_generics, _) = target.generics.split_for_impl();
+        let self_ty: Type = parse_quote!(#ident #ty_generics);
+        let trait_args = self.trait_args.as_ref();
+        let item = &instantiate(item, trait_args)?;
+        let mut generated = impl_header(item, self_ty, &target.generics, trait_args)?;
+        let trait_path = generated.trait_.as_ref().unwrap().1.clone();
+        let field_ty = self.field_ty;
+        generated
+            .generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#field_ty: #trait_path));
+
+        let mut errors: Vec<Error> = Vec::new();
+        for trait_item in &item.items {
+            if let Ok(f) = trait_item.function_or_method() {
+                match self.delegate_fn(&trait_path, f) {
+                    Ok(f) => generated.items.push(ImplItem::Fn(f)),
+                    // provided methods which can't be delegated keep their default body
+                    Err(_) if f.block().is_some() => {}
+                    Err(e) => errors.push(e),
+                }
+            } else if let Ok(c) = trait_item.constant() {
+                let ident = c.ident();
+                let expr = parse_quote!(<#field_ty as #trait_path>::#ident);
+                generated.items.push(ImplItem::Const(impl_const(c, expr)));
+            } else if let Ok(t) = trait_item.type_alias() {
+                let ident = t.ident();
+                let (_, ty_generics, _) = t.generics().split_for_impl();
+                let ty = parse_quote!(<#field_ty as #trait_path>::#ident #ty_generics);
+                generated.items.push(ImplItem::Type(impl_type(t, ty)));
+            }
+        }
+        combine(errors)?;
+        Ok(generated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_quote_eq;
+
+    #[test]
+    fn test_delegate() {
+        let item: ItemTrait = parse_quote! {
+            trait Store<K> {
+                type Iter<'a>: Iterator where Self: 'a;
+                const CAPACITY: usize;
+                fn get(&self, key: &K) -> Option<&str>;
+                fn set(&mut self, key: K, (a, b): (u8, u8));
+                fn into_vec(self) -> Vec<u8>;
+                async fn fetch<T: Default>(&self) -> T;
+                fn count(_: u8) -> usize { 0 }
+                fn dup(&self) -> Self where Self: Sized { todo!() }
+            }
+        };
+        let target: ItemStruct = parse_quote! {
+            struct Wrapper<S> {
+                name: String,
+                inner: S,
+            }
+        };
+        let generated = DelegateImpl::new(&target, parse_quote!(inner))
+            .unwrap()
+            .build(&item)
+            .unwrap();
+        let expected: ItemImpl = parse_quote! {
+            impl<K, S> Store<K> for Wrapper<S> where S: Store<K> {
+                type Iter<'a> = <S as Store<K> >::Iter<'a> where Self: 'a;
+                const CAPACITY: usize = <S as Store<K> >::CAPACITY;
+                fn get(&self, key: &K) -> Option<&str> {
+                    <S as Store<K> >::get(&self.inner, key)
+                }
+                fn set(&mut self, key: K, arg2: (u8, u8)) {
+                    <S as Store<K> >::set(&mut self.inner, key, arg2)
+                }
+                fn into_vec(self) -> Vec<u8> {
+                    <S as Store<K> >::into_vec(self.inner)
+                }
+                async fn fetch<T: Default>(&self) -> T {
+                    <S as Store<K> >::fetch::<T>(&self.inner).await
+                }
+                fn count(arg0: u8) -> usize {
+                    <S as Store<K> >::count(arg0)
+                }
+            }
+        };
+        assert_quote_eq!(generated, expected);
+    }
+
+    #[test]
+    fn test_delegate_trait_args() {
+        let item: ItemTrait = parse_quote! {
+            trait Store<K> {
+                fn get(&self, key: &K) -> Option<&str>;
+            }
+        };
+        let target: ItemStruct = parse_quote! {
+            struct Wrapper<K, S> {
+                inner: S,
+                keys: Vec<K>,
+            }
+        };
+        let generated = DelegateImpl::new(&target, parse_quote!(inner))
+            .unwrap()
+            .trait_args(parse_quote!(<K>))
+            .build(&item)
+            .unwrap();
+        let expected: ItemImpl = parse_quote! {
+            impl<K, S> Store<K> for Wrapper<K, S> where S: Store<K> {
+                fn get(&self, key: &K) -> Option<&str> {
+                    <S as Store<K> >::get(&self.inner, key)
+                }
+            }
+        };
+        assert_quote_eq!(generated, expected);
+    }
+
+    #[test]
+    fn test_delegate_errors() {
+        let target: ItemStruct = parse_quote! {
+            struct Wrapper(u8);
+        };
+        let err = DelegateImpl::new(&target, parse_quote!(1)).err().unwrap();
+        assert_eq!(err.to_string(), "no field `1` in struct `Wrapper`");
+
+        let item: ItemTrait = parse_quote! {
+            trait A {
+                type Item;
+                fn new() -> Self;
+                fn dup(&self) -> Option<Self>;
+                fn extend(&mut self, other: Vec<Self>);
+                fn item(&self, item: Self::Item) -> <Self as A>::Item;
+            }
+        };
+        let err = DelegateImpl::new(&target, parse_quote!(0))
+            .unwrap()
+            .build(&item)
+            .err()
+            .unwrap();
+        let messages: Vec<_> = err.into_iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "methods returning `Self` can't be delegated",
+                "methods returning `Self` can't be delegated",
+                "parameters referencing `Self` can't be delegated",
+            ]
+        );
+    }
+}
@@ -0,0 +1,348 @@
+use crate::attribute::AttributeExt;
+use crate::meta::{Meta1, MetaExt, MetaList1, NestedMeta};
+use crate::punctuated::PunctuatedExt;
+use proc_macro2::Span;
+use syn::{parse_quote, Attribute, Error, Ident, Path, Result};
+
+/// Built-in derive macros of the standard library
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BuiltinDerive {
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+}
+
+impl BuiltinDerive {
+    pub const ALL: [Self; 9] = [
+        Self::Clone,
+        Self::Copy,
+        Self::Debug,
+        Self::Default,
+        Self::Eq,
+        Self::Hash,
+        Self::Ord,
+        Self::PartialEq,
+        Self::PartialOrd,
+    ];
+
+    /// Returns the name of the derive macro
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Clone => "Clone",
+            Self::Copy => "Copy",
+            Self::Debug => "Debug",
+            Self::Default => "Default",
+            Self::Eq => "Eq",
+            Self::Hash => "Hash",
+            Self::Ord => "Ord",
+            Self::PartialEq => "PartialEq",
+            Self::PartialOrd => "PartialOrd",
+        }
+    }
+
+    /// Returns the module of the derive macro in `core` and `std`
+    fn module(self) -> &'static str {
+        match self {
+            Self::Clone => "clone",
+            Self::Copy => "marker",
+            Self::Debug => "fmt",
+            Self::Default => "default",
+            Self::Eq | Self::Ord | Self::PartialEq | Self::PartialOrd => "cmp",
+            Self::Hash => "hash",
+        }
+    }
+
+    /// Returns the built-in derive of `path`.
+    ///
+    /// `Debug`, `std::fmt::Debug`, `core::fmt::Debug` and `::core::fmt::Debug` are all `Debug`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let segments: Vec<_> = path.segments.iter().map(|s| &s.ident).collect();
+        let (name, module) = match segments.as_slice() {
+            [name] if path.leading_colon.is_none() => (name, None),
+            [krate, module, name] if *krate == "std" || *krate == "core" => (name, Some(module)),
+            _ => return None,
+        };
+        let derive = Self::ALL.iter().copied().find(|d| *name == d.name())?;
+        match module {
+            Some(module) if *module != derive.module() => None,
+            _ => Some(derive),
+        }
+    }
+
+    /// Constructs and returns a bare path of the derive macro
+    pub fn to_path(self) -> Path {
+        Ident::new(self.name(), Span::call_site()).into()
+    }
+}
+
+/// Returns a key to compare derive paths; built-in derives share a key with their full paths
+fn derive_key(path: &Path) -> String {
+    match BuiltinDerive::from_path(path) {
+        Some(derive) => derive.name().to_owned(),
+        None => {
+            let segments: Vec<_> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+            let prefix = if path.leading_colon.is_some() {
+                "::"
+            } else {
+                ""
+            };
+            format!("{}{}", prefix, segments.join("::"))
+        }
+    }
+}
+
+fn nested_key(nested: &NestedMeta) -> Option<String> {
+    match nested {
+        NestedMeta::Meta(Meta1::Path(path)) => Some(derive_key(path)),
+        _ => None,
+    }
+}
+
+fn is_derive(attr: &Attribute) -> bool {
+    attr.path().is_ident("derive")
+}
+
+fn derive_paths(list: &MetaList1) -> Result<Vec<Path>> {
+    list.nested
+        .iter()
+        .map(|nested| match nested {
+            NestedMeta::Meta(Meta1::Path(path)) => Ok(path.clone()),
+            other => Err(Error::new_spanned(other, "expected path of derive macro")),
+        })
+        .collect()
+}
+
+/// Extension for derive lists of `Vec<[syn::Attribute]>`
+///
+/// Only plain `#[derive(...)]` attributes are edited.
+/// `#[cfg_attr(..., derive(...))]` attributes are kept separate and untouched.
+pub trait DeriveAttrsExt {
+    /// Returns paths of every plain `#[derive(...)]` attribute
+    fn derives(&self) -> Result<Vec<Path>>;
+
+    /// Returns `true` if a path equivalent to `path` is derived by a plain `#[derive(...)]`
+    fn has_derive(&self, path: &Path) -> Result<bool>;
+
+    /// Adds `path` to the first `#[derive(...)]` unless already derived.
+    ///
+    /// A new attribute is pushed if there is no derive attribute.
+    /// Returns `true` if added.
+    fn add_derive(&mut self, path: Path) -> Result<bool>;
+
+    /// Removes paths equivalent to `path` from every `#[derive(...)]`.
+    ///
+    /// Attributes emptied by the removal are dropped. Returns `true` if any is removed.
+    fn remove_derive(&mut self, path: &Path) -> Result<bool>;
+
+    /// Merges every `#[derive(...)]` into the first one without duplicates
+    fn merge_derives(&mut self) -> Result<()>;
+
+    /// Returns predicates and derive paths of `#[cfg_attr(<predicate>, derive(...))]`
+    fn conditional_derives(&self) -> Result<Vec<(NestedMeta, Vec<Path>)>>;
+}
+
+impl DeriveAttrsExt for Vec<Attribute> {
+    fn derives(&self) -> Result<Vec<Path>> {
+        let mut paths = Vec::new();
+        for attr in self.iter().filter(|attr| is_derive(attr)) {
+            paths.extend(derive_paths(&attr.promoted_list()?)?);
+        }
+        Ok(paths)
+    }
+
+    fn has_derive(&self, path: &Path) -> Result<bool> {
+        let key = derive_key(path);
+        Ok(self.derives()?.iter().any(|p| derive_key(p) == key))
+    }
+
+    fn add_derive(&mut self, path: Path) -> Result<bool> {
+        if self.has_derive(&path)? {
+            return Ok(false);
+        }
+        match self.iter_mut().find(|attr| is_derive(attr)) {
+            Some(attr) => attr.try_promoted_list_mut(Default::default(), |list| {
+                list.nested.push(NestedMeta::Meta(Meta1::Path(path)));
+                Ok(())
+            })?,
+            None => self.push(parse_quote!(#[derive(#path)])),
+        }
+        Ok(true)
+    }
+
+    fn remove_derive(&mut self, path: &Path) -> Result<bool> {
+        let key = Some(derive_key(path));
+        let mut removed = false;
+        let mut emptied = Vec::new();
+        for (i, attr) in self.iter_mut().enumerate() {
+            if !is_derive(attr) {
+                continue;
+            }
+            attr.try_promoted_list_mut(Default::default(), |list| {
+                let len = list.nested.len();
+                list.nested.retain(|nested| nested_key(nested) != key);
+                if list.nested.len() != len {
+                    removed = true;
+                    if list.nested.is_empty() {
+                        emptied.push(i);
+                    }
+                }
+                Ok(())
+            })?;
+        }
+        *self = std::mem::take(self)
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !emptied.contains(i))
+            .map(|(_, attr)| attr)
+            .collect();
+        Ok(removed)
+    }
+
+    fn merge_derives(&mut self) -> Result<()> {
+        let first = match self.iter().position(is_derive) {
+            Some(first) => first,
+            None => return Ok(()),
+        };
+        let mut others = Vec::new();
+        for attr in self.iter().skip(first + 1).filter(|attr| is_derive(attr)) {
+            let list = attr.promoted_list()?;
+            derive_paths(&list)?;
+            others.extend(list.nested);
+        }
+        self[first].try_promoted_list_mut(Default::default(), |list| {
+            derive_paths(list)?;
+            let nested = std::mem::take(&mut list.nested);
+            let mut unique = crate::meta::PunctuatedNestedMeta::new();
+            unique.extend_unique(nested.into_iter().chain(others), nested_key);
+            list.nested = unique;
+            Ok(())
+        })?;
+        *self = std::mem::take(self)
+            .into_iter()
+            .enumerate()
+            .filter(|(i, attr)| *i <= first || !is_derive(attr))
+            .map(|(_, attr)| attr)
+            .collect();
+        Ok(())
+    }
+
+    fn conditional_derives(&self) -> Result<Vec<(NestedMeta, Vec<Path>)>> {
+        let mut derives = Vec::new();
+        for attr in self.iter().filter(|attr| attr.path().is_ident("cfg_attr")) {
+            let meta = attr.parse_meta()?;
+            let mut nested = meta.list()?.nested.iter();
+            let predicate = nested
+                .next()
+                .ok_or_else(|| Error::new_spanned(attr, "cfg_attr expects a predicate"))?;
+            let mut paths = Vec::new();
+            for nested in nested {
+                if let NestedMeta::Meta(Meta1::List(list)) = nested {
+                    if list.path.is_ident("derive") {
+                        paths.extend(derive_paths(list)?);
+                    }
+                }
+            }
+            if !paths.is_empty() {
+                derives.push((predicate.clone(), paths));
+            }
+        }
+        Ok(derives)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_quote_eq;
+    use crate::test::attrs;
+
+    #[test]
+    fn test_builtin_derive() {
+        let paths: Vec<Path> = vec![
+            parse_quote!(Debug),
+            parse_quote!(std::fmt::Debug),
+            parse_quote!(::core::fmt::Debug),
+        ];
+        for path in &paths {
+            assert_eq!(BuiltinDerive::from_path(path), Some(BuiltinDerive::Debug));
+        }
+        assert_eq!(
+            BuiltinDerive::from_path(&parse_quote!(std::cmp::Debug)),
+            None
+        );
+        assert_eq!(
+            BuiltinDerive::from_path(&parse_quote!(serde::Serialize)),
+            None
+        );
+        assert_quote_eq!(BuiltinDerive::Hash.to_path(), quote::quote!(Hash));
+    }
+
+    #[test]
+    fn test_add_remove_derive() {
+        let mut attrs = attrs(parse_quote! {
+            #[derive(std::fmt::Debug, Clone)]
+            #[cfg_attr(feature = "serde", derive(Clone))]
+            struct A;
+        });
+        assert!(!attrs.add_derive(parse_quote!(Debug)).unwrap());
+        assert!(attrs.add_derive(parse_quote!(PartialEq)).unwrap());
+        assert!(attrs
+            .has_derive(&parse_quote!(core::cmp::PartialEq))
+            .unwrap());
+        assert!(attrs.remove_derive(&parse_quote!(Clone)).unwrap());
+        assert!(!attrs.remove_derive(&parse_quote!(Clone)).unwrap());
+        let expected = quote::quote! {
+            #[derive(std::fmt::Debug, PartialEq)]
+            #[cfg_attr(feature = "serde", derive(Clone))]
+        };
+        assert_quote_eq!(quote::quote!(#(#attrs)*), expected);
+
+        let mut attrs = Vec::new();
+        assert!(attrs.add_derive(parse_quote!(Copy)).unwrap());
+        assert_quote_eq!(quote::quote!(#(#attrs)*), quote::quote!(#[derive(Copy)]));
+        attrs.remove_derive(&parse_quote!(Copy)).unwrap();
+        assert!(attrs.is_empty());
+    }
+
+    #[test]
+    fn test_remove_derive_keeps_empty() {
+        let mut attrs = attrs(parse_quote! {
+            #[derive()]
+            #[derive(Clone)]
+            struct A;
+        });
+        assert!(attrs.remove_derive(&parse_quote!(Clone)).unwrap());
+        assert_quote_eq!(quote::quote!(#(#attrs)*), quote::quote!(#[derive()]));
+    }
+
+    #[test]
+    fn test_merge_derives() {
+        let mut attrs = attrs(parse_quote! {
+            #[derive(Debug)]
+            #[doc = "doc"]
+            #[derive(Clone, core::fmt::Debug)]
+            #[cfg_attr(test, derive(Default))]
+            #[derive(Copy)]
+            struct A;
+        });
+        attrs.merge_derives().unwrap();
+        let expected = quote::quote! {
+            #[derive(Debug, Clone, Copy)]
+            #[doc = "doc"]
+            #[cfg_attr(test, derive(Default))]
+        };
+        assert_quote_eq!(quote::quote!(#(#attrs)*), expected);
+
+        let conditional = attrs.conditional_derives().unwrap();
+        assert_eq!(conditional.len(), 1);
+        assert_quote_eq!(conditional[0].0, quote::quote!(test));
+        assert_eq!(conditional[0].1.len(), 1);
+        assert_quote_eq!(conditional[0].1[0], BuiltinDerive::Default.to_path());
+    }
+}
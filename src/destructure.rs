@@ -0,0 +1,216 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{Field, Fields, Ident, Index, Member, Variant};
+
+/// How fields are bound by a destructuring pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingMode {
+    /// `a: __a`
+    Value,
+    /// `a: ref __a`
+    Ref,
+    /// `a: ref mut __a`
+    RefMut,
+}
+
+/// A field bound by [Destructure]
+#[derive(Clone)]
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+pub struct Binding<'a> {
+    pub member: Member,
+    pub field: &'a Field,
+    /// Name of the binding, e.g. `__a` or `__0`
+    pub ident: Ident,
+}
+
+/// Generator of destructuring patterns and constructor expressions over struct or variant fields
+///
+/// Binding names are `__` + prefix + member, like `__a` or `__other_0`, with mixed-site
+/// hygiene so that they never collide with user code.
+pub struct Destructure<'a> {
+    path: TokenStream,
+    fields: &'a Fields,
+    mode: BindingMode,
+    prefix: String,
+}
+
+impl<'a> Destructure<'a> {
+    /// Destructures `fields` of `path`, e.g. `Self` or `Enum::Variant`
+    pub fn new<P: ToTokens>(path: P, fields: &'a Fields) -> Self {
+        Self {
+            path: path.into_token_stream(),
+            fields,
+            mode: BindingMode::Value,
+            prefix: String::new(),
+        }
+    }
+
+    /// Destructures `variant` of `enum_path`
+    pub fn variant<P: ToTokens>(enum_path: P, variant: &'a Variant) -> Self {
+        let ident = &variant.ident;
+        Self::new(quote!(#enum_path::#ident), &variant.fields)
+    }
+
+    /// Sets the binding mode. [BindingMode::Value] by default.
+    pub fn mode(mut self, mode: BindingMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the prefix of binding names to tell apart multiple destructurings, e.g. `other_`
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_owned();
+        self
+    }
+
+    /// Returns every field with its binding
+    pub fn bindings(&self) -> Vec<Binding<'a>> {
+        self.fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let (member, ident) = match &field.ident {
+                    Some(ident) => (
+                        Member::Named(ident.clone()),
+                        format_ident!("__{}{}", self.prefix, ident),
+                    ),
+                    None => (
+                        Member::Unnamed(Index::from(i)),
+                        format_ident!("__{}{}", self.prefix, i),
+                    ),
+                };
+                Binding {
+                    member,
+                    field,
+                    ident: Ident::new(&ident.to_string(), Span::mixed_site()),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns `Path { a: __a, b: __b }`, `Path(__0, __1)` or `Path`
+    pub fn pattern(&self) -> TokenStream {
+        self.partial_pattern(|_| true)
+    }
+
+    /// Returns a pattern binding only fields accepted by `filter`, ending with `..` if any is skipped
+    pub fn partial_pattern<F>(&self, mut filter: F) -> TokenStream
+    where
+        F: FnMut(&Binding) -> bool,
+    {
+        let path = &self.path;
+        let mode = match self.mode {
+            BindingMode::Value => quote!(),
+            BindingMode::Ref => quote!(ref),
+            BindingMode::RefMut => quote!(ref mut),
+        };
+        let bindings = self.bindings();
+        let count = bindings.len();
+        let bound: Vec<_> = bindings
+            .into_iter()
+            .filter(|binding| filter(binding))
+            .collect();
+        match self.fields {
+            Fields::Unit => quote!(#path),
+            Fields::Unnamed(_) if bound.len() == count => {
+                let idents = bound.iter().map(|binding| &binding.ident);
+                quote!(#path(#(#mode #idents),*))
+            }
+            // tuple fields skipped in the middle are bound by their index, like `Path { 1: __1, .. }`
+            _ => {
+                let rest = if bound.len() < count {
+                    Some(quote!(..))
+                } else {
+                    None
+                };
+                let fields = bound
+                    .iter()
+                    .map(|Binding { member, ident, .. }| quote!(#member: #mode #ident));
+                quote!(#path { #(#fields,)* #rest })
+            }
+        }
+    }
+
+    /// Returns `Path { a: f(a), b: f(b) }`, `Path(f(0), f(1))` or `Path`
+    pub fn construct<F>(&self, mut f: F) -> TokenStream
+    where
+        F: FnMut(&Binding) -> TokenStream,
+    {
+        let path = &self.path;
+        let bindings = self.bindings();
+        let exprs = bindings.iter().map(&mut f);
+        match self.fields {
+            Fields::Named(_) => {
+                let members = bindings.iter().map(|binding| &binding.member);
+                quote!(#path { #(#members: #exprs),* })
+            }
+            Fields::Unnamed(_) => quote!(#path(#(#exprs),*)),
+            Fields::Unit => quote!(#path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_quote_eq;
+    use syn::{parse_quote, Data, DeriveInput};
+
+    #[test]
+    fn test_struct() {
+        let item: DeriveInput = parse_quote! {
+            struct A { a: u8, b: String }
+        };
+        let fields = match &item.data {
+            Data::Struct(data) => &data.fields,
+            _ => unreachable!(),
+        };
+        let destructure = Destructure::new(quote!(Self), fields).mode(BindingMode::Ref);
+        assert_quote_eq!(
+            destructure.pattern(),
+            quote! { Self { a: ref __a, b: ref __b, } }
+        );
+        let partial = destructure.partial_pattern(|binding| binding.member != parse_quote!(a));
+        assert_quote_eq!(partial, quote! { Self { b: ref __b, .. } });
+        let constructed = destructure.construct(|binding| {
+            let ident = &binding.ident;
+            quote!(Clone::clone(#ident))
+        });
+        assert_quote_eq!(
+            constructed,
+            quote! { Self { a: Clone::clone(__a), b: Clone::clone(__b) } }
+        );
+    }
+
+    #[test]
+    fn test_variants() {
+        let item: DeriveInput = parse_quote! {
+            enum E { T(u8, u16), U }
+        };
+        let variants = match &item.data {
+            Data::Enum(data) => &data.variants,
+            _ => unreachable!(),
+        };
+        let destructure = Destructure::variant(quote!(E), &variants[0])
+            .mode(BindingMode::RefMut)
+            .prefix("other_");
+        assert_quote_eq!(
+            destructure.pattern(),
+            quote! { E::T(ref mut __other_0, ref mut __other_1) }
+        );
+        let partial = destructure.partial_pattern(|binding| binding.member == parse_quote!(1));
+        assert_quote_eq!(partial, quote! { E::T { 1: ref mut __other_1, .. } });
+        let constructed = destructure.construct(|binding| {
+            let ty = &binding.field.ty;
+            quote!(<#ty as Default>::default())
+        });
+        assert_quote_eq!(
+            constructed,
+            quote! { E::T(<u8 as Default>::default(), <u16 as Default>::default()) }
+        );
+
+        let destructure = Destructure::variant(quote!(E), &variants[1]);
+        assert_quote_eq!(destructure.pattern(), quote! { E::U });
+        assert_quote_eq!(destructure.construct(|_| unreachable!()), quote! { E::U });
+    }
+}
@@ -0,0 +1,16 @@
+use syn::{Error, Result};
+
+/// Combines `errors` into a single error, or `Ok` if there is none
+pub(crate) fn combine<I>(errors: I) -> Result<()>
+where
+    I: IntoIterator<Item = Error>,
+{
+    let mut errors = errors.into_iter();
+    match errors.next() {
+        Some(mut first) => {
+            first.extend(errors);
+            Err(first)
+        }
+        None => Ok(()),
+    }
+}
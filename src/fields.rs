@@ -0,0 +1,449 @@
+use crate::punctuated::PunctuatedExt;
+use syn::{
+    punctuated::{Pair, Punctuated},
+    token::Comma,
+    Attribute, Data, DeriveInput, Error, Field, Fields, FieldsNamed, FieldsUnnamed, Ident, Index,
+    Member, Result, Variant,
+};
+
+/// Shape of [syn::Fields]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldsShape {
+    /// `{ a: A, b: B }`
+    Named,
+    /// `(A, B)`
+    Unnamed,
+    /// No fields
+    Unit,
+}
+
+impl FieldsShape {
+    pub fn of(fields: &Fields) -> Self {
+        match fields {
+            Fields::Named(_) => FieldsShape::Named,
+            Fields::Unnamed(_) => FieldsShape::Unnamed,
+            Fields::Unit => FieldsShape::Unit,
+        }
+    }
+}
+
+fn member_of(index: usize, field: &Field) -> Member {
+    match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(index)),
+    }
+}
+
+fn position(list: &Punctuated<Field, Comma>, member: &Member) -> Option<usize> {
+    list.iter()
+        .enumerate()
+        .position(|(i, field)| member_of(i, field) == *member)
+}
+
+/// Uniform field access for [syn::Fields], [syn::DeriveInput], [syn::Variant], [syn::ItemStruct] and [syn::ItemUnion]
+///
+/// Enums have fields in each variant; their [syn::DeriveInput] returns `Err` for every method.
+pub trait FieldsLike {
+    /// Returns the shape of the fields
+    fn shape(&self) -> Result<FieldsShape>;
+    /// Returns the fields. `None` for unit shape.
+    fn field_list(&self) -> Result<Option<&Punctuated<Field, Comma>>>;
+    /// Returns the mutable fields. `None` for unit shape.
+    fn field_list_mut(&mut self) -> Result<Option<&mut Punctuated<Field, Comma>>>;
+    /// Replaces the fields, changing the shape if needed. Struct semicolons follow the new shape.
+    fn set_fields(&mut self, fields: Fields) -> Result<()>;
+
+    /// Returns a copy of the fields as [syn::Fields]
+    fn to_fields(&self) -> Result<Fields> {
+        let list = self.field_list()?.cloned();
+        Ok(match (self.shape()?, list) {
+            (FieldsShape::Named, Some(named)) => Fields::Named(FieldsNamed {
+                brace_token: Default::default(),
+                named,
+            }),
+            (FieldsShape::Unnamed, Some(unnamed)) => Fields::Unnamed(FieldsUnnamed {
+                paren_token: Default::default(),
+                unnamed,
+            }),
+            _ => Fields::Unit,
+        })
+    }
+
+    /// Returns every field with its member, `a` for named fields and `0` for unnamed fields
+    fn members(&self) -> Result<Vec<(Member, &Field)>> {
+        Ok(self
+            .field_list()?
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .map(|(i, field)| (member_of(i, field), field))
+            .collect())
+    }
+
+    /// Returns the field of `member`
+    fn field(&self, member: &Member) -> Result<Option<&Field>> {
+        Ok(self.field_list()?.and_then(|list| {
+            let index = position(list, member)?;
+            list.iter().nth(index)
+        }))
+    }
+
+    /// Returns the mutable field of `member`
+    fn field_mut(&mut self, member: &Member) -> Result<Option<&mut Field>> {
+        Ok(self.field_list_mut()?.and_then(|list| {
+            let index = position(list, member)?;
+            list.iter_mut().nth(index)
+        }))
+    }
+
+    /// Returns the named field `name`
+    fn find_field<N>(&self, name: &N) -> Result<Option<&Field>>
+    where
+        N: ?Sized,
+        Ident: PartialEq<N>,
+    {
+        Ok(self.field_list()?.and_then(|list| {
+            list.iter()
+                .find(|field| field.ident.as_ref().is_some_and(|ident| *ident == *name))
+        }))
+    }
+
+    /// Returns attributes of the field of `member`
+    fn field_attrs(&self, member: &Member) -> Result<Option<&[Attribute]>> {
+        Ok(self.field(member)?.map(|field| field.attrs.as_slice()))
+    }
+
+    /// Pushes `field` and returns its member, keeping the trailing comma if any.
+    ///
+    /// Unit shape becomes named or unnamed following `field`.
+    fn add_field(&mut self, field: Field) -> Result<Member> {
+        let shape = self.shape()?;
+        match (shape, &field.ident) {
+            (FieldsShape::Unit, Some(_)) => self.set_fields(Fields::Named(FieldsNamed {
+                brace_token: Default::default(),
+                named: Punctuated::new(),
+            }))?,
+            (FieldsShape::Unit, None) => self.set_fields(Fields::Unnamed(FieldsUnnamed {
+                paren_token: Default::default(),
+                unnamed: Punctuated::new(),
+            }))?,
+            (FieldsShape::Named, None) => {
+                return Err(Error::new_spanned(&field, "expected a named field"))
+            }
+            (FieldsShape::Unnamed, Some(ident)) => {
+                return Err(Error::new_spanned(
+                    ident,
+                    "expected an unnamed field for a tuple",
+                ))
+            }
+            _ => {}
+        }
+        if let Some(ident) = &field.ident {
+            if self.find_field(ident)?.is_some() {
+                return Err(Error::new_spanned(
+                    ident,
+                    format!("field `{}` is already declared", ident),
+                ));
+            }
+        }
+        let list = self.field_list_mut()?.expect("not a unit");
+        let member = member_of(list.len(), &field);
+        list.insert_pair(list.len(), Pair::End(field));
+        Ok(member)
+    }
+
+    /// Removes and returns the field of `member`, keeping punctuation of the others.
+    ///
+    /// Members of unnamed fields after it are shifted.
+    fn remove_field(&mut self, member: &Member) -> Result<Option<Field>> {
+        Ok(self.field_list_mut()?.and_then(|list| {
+            let index = position(list, member)?;
+            Some(list.remove(index).into_value())
+        }))
+    }
+
+    /// Converts into named fields, naming unnamed fields by `names(index, field)`
+    fn make_named<F>(&mut self, mut names: F) -> Result<()>
+    where
+        F: FnMut(usize, &Field) -> Ident,
+    {
+        let mut named = match self.to_fields()? {
+            Fields::Named(_) => return Ok(()),
+            Fields::Unnamed(fields) => fields.unnamed,
+            Fields::Unit => Punctuated::new(),
+        };
+        for (i, field) in named.iter_mut().enumerate() {
+            field.ident = Some(names(i, field));
+            field.colon_token = Some(Default::default());
+        }
+        self.set_fields(Fields::Named(FieldsNamed {
+            brace_token: Default::default(),
+            named,
+        }))
+    }
+
+    /// Converts into unnamed fields, dropping names
+    fn make_unnamed(&mut self) -> Result<()> {
+        let mut unnamed = match self.to_fields()? {
+            Fields::Named(fields) => fields.named,
+            Fields::Unnamed(_) => return Ok(()),
+            Fields::Unit => Punctuated::new(),
+        };
+        for field in unnamed.iter_mut() {
+            field.ident = None;
+            field.colon_token = None;
+        }
+        self.set_fields(Fields::Unnamed(FieldsUnnamed {
+            paren_token: Default::default(),
+            unnamed,
+        }))
+    }
+
+    /// Converts into unit. `Err` if any field exists.
+    fn make_unit(&mut self) -> Result<()> {
+        if let Some((_, field)) = self.members()?.first() {
+            return Err(Error::new_spanned(
+                field,
+                "fields must be removed before converting to unit",
+            ));
+        }
+        self.set_fields(Fields::Unit)
+    }
+}
+
+fn fields_list(fields: &Fields) -> Option<&Punctuated<Field, Comma>> {
+    match fields {
+        Fields::Named(fields) => Some(&fields.named),
+        Fields::Unnamed(fields) => Some(&fields.unnamed),
+        Fields::Unit => None,
+    }
+}
+
+fn fields_list_mut(fields: &mut Fields) -> Option<&mut Punctuated<Field, Comma>> {
+    match fields {
+        Fields::Named(fields) => Some(&mut fields.named),
+        Fields::Unnamed(fields) => Some(&mut fields.unnamed),
+        Fields::Unit => None,
+    }
+}
+
+fn named_only(fields: Fields) -> Result<FieldsNamed> {
+    match fields {
+        Fields::Named(fields) => Ok(fields),
+        fields => Err(Error::new_spanned(fields, "union fields must be named")),
+    }
+}
+
+impl FieldsLike for Fields {
+    fn shape(&self) -> Result<FieldsShape> {
+        Ok(FieldsShape::of(self))
+    }
+    fn field_list(&self) -> Result<Option<&Punctuated<Field, Comma>>> {
+        Ok(fields_list(self))
+    }
+    fn field_list_mut(&mut self) -> Result<Option<&mut Punctuated<Field, Comma>>> {
+        Ok(fields_list_mut(self))
+    }
+    fn set_fields(&mut self, fields: Fields) -> Result<()> {
+        *self = fields;
+        Ok(())
+    }
+}
+
+impl FieldsLike for Variant {
+    fn shape(&self) -> Result<FieldsShape> {
+        Ok(FieldsShape::of(&self.fields))
+    }
+    fn field_list(&self) -> Result<Option<&Punctuated<Field, Comma>>> {
+        Ok(fields_list(&self.fields))
+    }
+    fn field_list_mut(&mut self) -> Result<Option<&mut Punctuated<Field, Comma>>> {
+        Ok(fields_list_mut(&mut self.fields))
+    }
+    fn set_fields(&mut self, fields: Fields) -> Result<()> {
+        self.fields = fields;
+        Ok(())
+    }
+}
+
+fn enum_error(item: &DeriveInput) -> Error {
+    match &item.data {
+        Data::Enum(data) => Error::new_spanned(
+            data.enum_token,
+            "enums have fields in each variant, not in the enum",
+        ),
+        _ => unreachable!(),
+    }
+}
+
+impl FieldsLike for DeriveInput {
+    fn shape(&self) -> Result<FieldsShape> {
+        match &self.data {
+            Data::Struct(data) => Ok(FieldsShape::of(&data.fields)),
+            Data::Union(_) => Ok(FieldsShape::Named),
+            Data::Enum(_) => Err(enum_error(self)),
+        }
+    }
+    fn field_list(&self) -> Result<Option<&Punctuated<Field, Comma>>> {
+        match &self.data {
+            Data::Struct(data) => Ok(fields_list(&data.fields)),
+            Data::Union(data) => Ok(Some(&data.fields.named)),
+            Data::Enum(_) => Err(enum_error(self)),
+        }
+    }
+    fn field_list_mut(&mut self) -> Result<Option<&mut Punctuated<Field, Comma>>> {
+        if let Data::Enum(_) = &self.data {
+            return Err(enum_error(self));
+        }
+        match &mut self.data {
+            Data::Struct(data) => Ok(fields_list_mut(&mut data.fields)),
+            Data::Union(data) => Ok(Some(&mut data.fields.named)),
+            Data::Enum(_) => unreachable!(),
+        }
+    }
+    fn set_fields(&mut self, fields: Fields) -> Result<()> {
+        if let Data::Enum(_) = &self.data {
+            return Err(enum_error(self));
+        }
+        match &mut self.data {
+            Data::Struct(data) => {
+                data.semi_token = match fields {
+                    Fields::Named(_) => None,
+                    _ => Some(Default::default()),
+                };
+                data.fields = fields;
+            }
+            Data::Union(data) => data.fields = named_only(fields)?,
+            Data::Enum(_) => unreachable!(),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "full")]
+impl FieldsLike for syn::ItemStruct {
+    fn shape(&self) -> Result<FieldsShape> {
+        Ok(FieldsShape::of(&self.fields))
+    }
+    fn field_list(&self) -> Result<Option<&Punctuated<Field, Comma>>> {
+        Ok(fields_list(&self.fields))
+    }
+    fn field_list_mut(&mut self) -> Result<Option<&mut Punctuated<Field, Comma>>> {
+        Ok(fields_list_mut(&mut self.fields))
+    }
+    fn set_fields(&mut self, fields: Fields) -> Result<()> {
+        self.semi_token = match fields {
+            Fields::Named(_) => None,
+            _ => Some(Default::default()),
+        };
+        self.fields = fields;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "full")]
+impl FieldsLike for syn::ItemUnion {
+    fn shape(&self) -> Result<FieldsShape> {
+        Ok(FieldsShape::Named)
+    }
+    fn field_list(&self) -> Result<Option<&Punctuated<Field, Comma>>> {
+        Ok(Some(&self.fields.named))
+    }
+    fn field_list_mut(&mut self) -> Result<Option<&mut Punctuated<Field, Comma>>> {
+        Ok(Some(&mut self.fields.named))
+    }
+    fn set_fields(&mut self, fields: Fields) -> Result<()> {
+        self.fields = named_only(fields)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_quote_eq;
+    use quote::{format_ident, quote};
+    use syn::{parse::Parser, parse_quote};
+
+    #[test]
+    fn test_members() {
+        let item: DeriveInput = parse_quote! {
+            struct A {
+                /// doc of a
+                a: u8,
+                #[serde(skip)]
+                b: u16,
+            }
+        };
+        let members: Vec<_> = item
+            .members()
+            .unwrap()
+            .into_iter()
+            .map(|(member, _)| quote!(#member).to_string())
+            .collect();
+        assert_eq!(members, ["a", "b"]);
+        assert!(item.find_field("b").unwrap().is_some());
+        assert!(item.field(&parse_quote!(0)).unwrap().is_none());
+        #[cfg(feature = "parsing")]
+        {
+            use crate::attribute::AttributeIteratorExt;
+            let attrs = item.field_attrs(&parse_quote!(a)).unwrap().unwrap();
+            assert_eq!(attrs.iter().doc().as_deref(), Some("doc of a"));
+        }
+
+        let item: DeriveInput = parse_quote! {
+            struct B(u8, u16);
+        };
+        let ty = &item.field(&parse_quote!(1)).unwrap().unwrap().ty;
+        assert_quote_eq!(ty, quote! { u16 });
+
+        let item: DeriveInput = parse_quote! {
+            enum C { V(u8) }
+        };
+        let err = item.members().err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "enums have fields in each variant, not in the enum"
+        );
+        let variant = match &item.data {
+            Data::Enum(data) => &data.variants[0],
+            _ => unreachable!(),
+        };
+        assert_eq!(variant.members().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_add_remove() {
+        let named = |tokens| Field::parse_named.parse2(tokens).unwrap();
+        let mut item: DeriveInput = parse_quote! {
+            struct A;
+        };
+        let member = item.add_field(parse_quote!(u8)).unwrap();
+        assert_quote_eq!(member, quote! { 0 });
+        item.add_field(parse_quote!(u16)).unwrap();
+        assert_quote_eq!(item, quote! { struct A(u8, u16); });
+        assert!(item.add_field(named(quote! { a: u8 })).is_err());
+
+        let removed = item.remove_field(&parse_quote!(0)).unwrap().unwrap();
+        assert_quote_eq!(removed.ty, quote! { u8 });
+        assert_quote_eq!(item, quote! { struct A(u16); });
+
+        item.make_named(|i, _| format_ident!("f{}", i)).unwrap();
+        assert_quote_eq!(item, quote! { struct A { f0: u16 } });
+        let err = item.add_field(named(quote! { f0: u8 })).err().unwrap();
+        assert_eq!(err.to_string(), "field `f0` is already declared");
+        assert!(item.make_unit().is_err());
+
+        item.make_unnamed().unwrap();
+        assert_quote_eq!(item, quote! { struct A(u16); });
+        item.remove_field(&parse_quote!(0)).unwrap();
+        item.make_unit().unwrap();
+        assert_quote_eq!(item, quote! { struct A; });
+
+        let mut item: DeriveInput = parse_quote! {
+            struct B { a: u8, }
+        };
+        item.add_field(named(quote! { b: u16 })).unwrap();
+        assert_quote_eq!(item, quote! { struct B { a: u8, b: u16, } });
+    }
+}
@@ -1,7 +1,11 @@
 use crate::ident::GetIdent;
+use crate::item_use::{use_tree_ident, ItemUseExt, UseEntry};
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use std::collections::HashSet;
 use syn::{
-    spanned::Spanned, Attribute, Ident, ImplItem, ImplItemFn, Item, ItemFn, ItemMod, Result,
-    TraitItem, TraitItemFn,
+    punctuated::Punctuated, spanned::Spanned, Attribute, Error, File, ForeignItem, ForeignItemFn,
+    Generics, Ident, ImplItem, ImplItemFn, Item, ItemFn, ItemMod, Path, Result, TraitItem,
+    TraitItemFn, Visibility,
 };
 
 /// Extension for [syn::Item]
@@ -14,11 +18,42 @@ pub trait ItemLike: Spanned {
     fn function_or_method(&self) -> Result<&dyn FunctionLike>;
     /// Returns const-like trait object of Item::Const, ImplItem::Const, or TraitItem::Const
     fn constant(&self) -> Result<&dyn ConstLike>;
+    /// Returns type-like trait object of Item::Type, ImplItem::Type, TraitItem::Type, or ForeignItem::Type
+    fn type_alias(&self) -> Result<&dyn TypeLike>;
+    /// Returns macro-like trait object of Item::Macro, ImplItem::Macro, TraitItem::Macro, or ForeignItem::Macro
+    fn macro_call(&self) -> Result<&dyn MacroLike>;
+    /// Returns static-like trait object of Item::Static or ForeignItem::Static
+    fn static_item(&self) -> Result<&dyn StaticLike>;
 
     /// Returns `true` if self matches `*ItemType`
     fn is_type(&self) -> bool;
     /// Returns `true` if self matches `*ItemMacro`
     fn is_macro(&self) -> bool;
+
+    /// Returns reference of visibility if the kind of item has one; otherwise `Err`
+    fn vis(&self) -> Result<&Visibility>;
+    /// Returns mutable reference of visibility if the kind of item has one; otherwise `Err`
+    fn vis_mut(&mut self) -> Result<&mut Visibility>;
+    /// Returns reference of generics if the kind of item has them; otherwise `Err`
+    fn generics(&self) -> Result<&Generics>;
+    /// Returns mutable reference of generics if the kind of item has them; otherwise `Err`
+    fn generics_mut(&mut self) -> Result<&mut Generics>;
+    /// Returns mutable reference of ident if the kind of item has one; otherwise `Err`
+    fn ident_mut(&mut self) -> Result<&mut Ident>;
+    /// Replaces ident of the item and returns the old one
+    fn set_ident(&mut self, ident: Ident) -> Result<Ident> {
+        Ok(std::mem::replace(self.ident_mut()?, ident))
+    }
+}
+
+fn missing<T>(item: &T, component: &str) -> Error
+where
+    T: quote::ToTokens,
+{
+    Error::new_spanned(
+        item,
+        format!("this kind of item doesn't have {}", component),
+    )
 }
 
 impl ItemLike for Item {
@@ -97,12 +132,135 @@ impl ItemLike for Item {
         }
     }
 
+    fn type_alias(&self) -> Result<&dyn TypeLike> {
+        match self {
+            Item::Type(t) => Ok(t),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "this item is not a type alias",
+            )),
+        }
+    }
+
+    fn macro_call(&self) -> Result<&dyn MacroLike> {
+        match self {
+            Item::Macro(m) => Ok(m),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "this item is not a macro call",
+            )),
+        }
+    }
+
+    fn static_item(&self) -> Result<&dyn StaticLike> {
+        match self {
+            Item::Static(s) => Ok(s),
+            other => Err(syn::Error::new_spanned(other, "this item is not a static")),
+        }
+    }
+
     fn is_type(&self) -> bool {
         matches!(self, Item::Type(_))
     }
     fn is_macro(&self) -> bool {
         matches!(self, Item::Macro(_))
     }
+
+    fn vis(&self) -> Result<&Visibility> {
+        use syn::Item::*;
+        let vis = match self {
+            Const(item) => &item.vis,
+            Enum(item) => &item.vis,
+            ExternCrate(item) => &item.vis,
+            Fn(item) => &item.vis,
+            Mod(item) => &item.vis,
+            Static(item) => &item.vis,
+            Struct(item) => &item.vis,
+            Trait(item) => &item.vis,
+            TraitAlias(item) => &item.vis,
+            Type(item) => &item.vis,
+            Union(item) => &item.vis,
+            Use(item) => &item.vis,
+            other => return Err(missing(other, "visibility")),
+        };
+        Ok(vis)
+    }
+
+    fn vis_mut(&mut self) -> Result<&mut Visibility> {
+        use syn::Item::*;
+        let vis = match self {
+            Const(item) => &mut item.vis,
+            Enum(item) => &mut item.vis,
+            ExternCrate(item) => &mut item.vis,
+            Fn(item) => &mut item.vis,
+            Mod(item) => &mut item.vis,
+            Static(item) => &mut item.vis,
+            Struct(item) => &mut item.vis,
+            Trait(item) => &mut item.vis,
+            TraitAlias(item) => &mut item.vis,
+            Type(item) => &mut item.vis,
+            Union(item) => &mut item.vis,
+            Use(item) => &mut item.vis,
+            other => return Err(missing(other, "visibility")),
+        };
+        Ok(vis)
+    }
+
+    fn generics(&self) -> Result<&Generics> {
+        use syn::Item::*;
+        let generics = match self {
+            Const(item) => &item.generics,
+            Enum(item) => &item.generics,
+            Fn(item) => &item.sig.generics,
+            Impl(item) => &item.generics,
+            Struct(item) => &item.generics,
+            Trait(item) => &item.generics,
+            TraitAlias(item) => &item.generics,
+            Type(item) => &item.generics,
+            Union(item) => &item.generics,
+            other => return Err(missing(other, "generics")),
+        };
+        Ok(generics)
+    }
+
+    fn generics_mut(&mut self) -> Result<&mut Generics> {
+        use syn::Item::*;
+        let generics = match self {
+            Const(item) => &mut item.generics,
+            Enum(item) => &mut item.generics,
+            Fn(item) => &mut item.sig.generics,
+            Impl(item) => &mut item.generics,
+            Struct(item) => &mut item.generics,
+            Trait(item) => &mut item.generics,
+            TraitAlias(item) => &mut item.generics,
+            Type(item) => &mut item.generics,
+            Union(item) => &mut item.generics,
+            other => return Err(missing(other, "generics")),
+        };
+        Ok(generics)
+    }
+
+    fn ident_mut(&mut self) -> Result<&mut Ident> {
+        use syn::Item::*;
+        let ident = match self {
+            Const(item) => &mut item.ident,
+            Enum(item) => &mut item.ident,
+            ExternCrate(item) => &mut item.ident,
+            Fn(item) => &mut item.sig.ident,
+            Macro(syn::ItemMacro {
+                ident: Some(ident), ..
+            }) => ident,
+            Mod(item) => &mut item.ident,
+            Static(item) => &mut item.ident,
+            Struct(item) => &mut item.ident,
+            Trait(item) => &mut item.ident,
+            TraitAlias(item) => &mut item.ident,
+            Type(item) => &mut item.ident,
+            Union(item) => &mut item.ident,
+            other => return Err(missing(other, "ident")),
+        };
+        Ok(ident)
+    }
 }
 
 impl ItemLike for ImplItem {
@@ -159,12 +317,91 @@ impl ItemLike for ImplItem {
         }
     }
 
+    fn type_alias(&self) -> Result<&dyn TypeLike> {
+        match self {
+            ImplItem::Type(t) => Ok(t),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "this item is not a type alias",
+            )),
+        }
+    }
+
+    fn macro_call(&self) -> Result<&dyn MacroLike> {
+        match self {
+            ImplItem::Macro(m) => Ok(m),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "this item is not a macro call",
+            )),
+        }
+    }
+
+    fn static_item(&self) -> Result<&dyn StaticLike> {
+        Err(syn::Error::new_spanned(self, "this item is not a static"))
+    }
+
     fn is_type(&self) -> bool {
         matches!(self, ImplItem::Type(_))
     }
     fn is_macro(&self) -> bool {
         matches!(self, ImplItem::Macro(_))
     }
+
+    fn vis(&self) -> Result<&Visibility> {
+        use syn::ImplItem::*;
+        let vis = match self {
+            Const(item) => &item.vis,
+            Fn(item) => &item.vis,
+            Type(item) => &item.vis,
+            other => return Err(missing(other, "visibility")),
+        };
+        Ok(vis)
+    }
+
+    fn vis_mut(&mut self) -> Result<&mut Visibility> {
+        use syn::ImplItem::*;
+        let vis = match self {
+            Const(item) => &mut item.vis,
+            Fn(item) => &mut item.vis,
+            Type(item) => &mut item.vis,
+            other => return Err(missing(other, "visibility")),
+        };
+        Ok(vis)
+    }
+
+    fn generics(&self) -> Result<&Generics> {
+        use syn::ImplItem::*;
+        let generics = match self {
+            Const(item) => &item.generics,
+            Fn(item) => &item.sig.generics,
+            Type(item) => &item.generics,
+            other => return Err(missing(other, "generics")),
+        };
+        Ok(generics)
+    }
+
+    fn generics_mut(&mut self) -> Result<&mut Generics> {
+        use syn::ImplItem::*;
+        let generics = match self {
+            Const(item) => &mut item.generics,
+            Fn(item) => &mut item.sig.generics,
+            Type(item) => &mut item.generics,
+            other => return Err(missing(other, "generics")),
+        };
+        Ok(generics)
+    }
+
+    fn ident_mut(&mut self) -> Result<&mut Ident> {
+        use syn::ImplItem::*;
+        let ident = match self {
+            Const(item) => &mut item.ident,
+            Fn(item) => &mut item.sig.ident,
+            Type(item) => &mut item.ident,
+            other => return Err(missing(other, "ident")),
+        };
+        Ok(ident)
+    }
 }
 
 impl ItemLike for TraitItem {
@@ -221,12 +458,207 @@ impl ItemLike for TraitItem {
         }
     }
 
+    fn type_alias(&self) -> Result<&dyn TypeLike> {
+        match self {
+            TraitItem::Type(t) => Ok(t),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "this item is not a type alias",
+            )),
+        }
+    }
+
+    fn macro_call(&self) -> Result<&dyn MacroLike> {
+        match self {
+            TraitItem::Macro(m) => Ok(m),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "this item is not a macro call",
+            )),
+        }
+    }
+
+    fn static_item(&self) -> Result<&dyn StaticLike> {
+        Err(syn::Error::new_spanned(self, "this item is not a static"))
+    }
+
     fn is_type(&self) -> bool {
         matches!(self, TraitItem::Type(_))
     }
     fn is_macro(&self) -> bool {
         matches!(self, TraitItem::Macro(_))
     }
+
+    /// Trait items don't have visibility; always `Err`
+    fn vis(&self) -> Result<&Visibility> {
+        Err(missing(self, "visibility"))
+    }
+
+    /// Trait items don't have visibility; always `Err`
+    fn vis_mut(&mut self) -> Result<&mut Visibility> {
+        Err(missing(self, "visibility"))
+    }
+
+    fn generics(&self) -> Result<&Generics> {
+        use syn::TraitItem::*;
+        let generics = match self {
+            Const(item) => &item.generics,
+            Fn(item) => &item.sig.generics,
+            Type(item) => &item.generics,
+            other => return Err(missing(other, "generics")),
+        };
+        Ok(generics)
+    }
+
+    fn generics_mut(&mut self) -> Result<&mut Generics> {
+        use syn::TraitItem::*;
+        let generics = match self {
+            Const(item) => &mut item.generics,
+            Fn(item) => &mut item.sig.generics,
+            Type(item) => &mut item.generics,
+            other => return Err(missing(other, "generics")),
+        };
+        Ok(generics)
+    }
+
+    fn ident_mut(&mut self) -> Result<&mut Ident> {
+        use syn::TraitItem::*;
+        let ident = match self {
+            Const(item) => &mut item.ident,
+            Fn(item) => &mut item.sig.ident,
+            Type(item) => &mut item.ident,
+            other => return Err(missing(other, "ident")),
+        };
+        Ok(ident)
+    }
+}
+
+impl ItemLike for ForeignItem {
+    fn attrs(&self) -> Result<&[Attribute]> {
+        use syn::ForeignItem::*;
+        let attrs = match self {
+            Fn(item) => &item.attrs,
+            Static(item) => &item.attrs,
+            Type(item) => &item.attrs,
+            Macro(item) => &item.attrs,
+            other => return Err(missing(other, "attrs")),
+        };
+        Ok(attrs)
+    }
+
+    fn attrs_mut(&mut self) -> Result<&mut Vec<Attribute>> {
+        use syn::ForeignItem::*;
+        let attrs = match self {
+            Fn(item) => &mut item.attrs,
+            Static(item) => &mut item.attrs,
+            Type(item) => &mut item.attrs,
+            Macro(item) => &mut item.attrs,
+            other => return Err(missing(other, "attrs")),
+        };
+        Ok(attrs)
+    }
+
+    fn function_or_method(&self) -> Result<&dyn FunctionLike> {
+        match self {
+            ForeignItem::Fn(f) => Ok(f),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "this item is not a function or method",
+            )),
+        }
+    }
+
+    /// Foreign items can't be const; always `Err`
+    fn constant(&self) -> Result<&dyn ConstLike> {
+        Err(syn::Error::new_spanned(self, "this item is not a const"))
+    }
+
+    fn type_alias(&self) -> Result<&dyn TypeLike> {
+        match self {
+            ForeignItem::Type(t) => Ok(t),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "this item is not a type alias",
+            )),
+        }
+    }
+
+    fn macro_call(&self) -> Result<&dyn MacroLike> {
+        match self {
+            ForeignItem::Macro(m) => Ok(m),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "this item is not a macro call",
+            )),
+        }
+    }
+
+    fn static_item(&self) -> Result<&dyn StaticLike> {
+        match self {
+            ForeignItem::Static(s) => Ok(s),
+            other => Err(syn::Error::new_spanned(other, "this item is not a static")),
+        }
+    }
+
+    fn is_type(&self) -> bool {
+        matches!(self, ForeignItem::Type(_))
+    }
+    fn is_macro(&self) -> bool {
+        matches!(self, ForeignItem::Macro(_))
+    }
+
+    fn vis(&self) -> Result<&Visibility> {
+        use syn::ForeignItem::*;
+        let vis = match self {
+            Fn(item) => &item.vis,
+            Static(item) => &item.vis,
+            Type(item) => &item.vis,
+            other => return Err(missing(other, "visibility")),
+        };
+        Ok(vis)
+    }
+
+    fn vis_mut(&mut self) -> Result<&mut Visibility> {
+        use syn::ForeignItem::*;
+        let vis = match self {
+            Fn(item) => &mut item.vis,
+            Static(item) => &mut item.vis,
+            Type(item) => &mut item.vis,
+            other => return Err(missing(other, "visibility")),
+        };
+        Ok(vis)
+    }
+
+    fn generics(&self) -> Result<&Generics> {
+        use syn::ForeignItem::*;
+        let generics = match self {
+            Fn(item) => &item.sig.generics,
+            Type(item) => &item.generics,
+            other => return Err(missing(other, "generics")),
+        };
+        Ok(generics)
+    }
+
+    fn generics_mut(&mut self) -> Result<&mut Generics> {
+        use syn::ForeignItem::*;
+        let generics = match self {
+            Fn(item) => &mut item.sig.generics,
+            Type(item) => &mut item.generics,
+            other => return Err(missing(other, "generics")),
+        };
+        Ok(generics)
+    }
+
+    fn ident_mut(&mut self) -> Result<&mut Ident> {
+        use syn::ForeignItem::*;
+        let ident = match self {
+            Fn(item) => &mut item.sig.ident,
+            Static(item) => &mut item.ident,
+            Type(item) => &mut item.ident,
+            other => return Err(missing(other, "ident")),
+        };
+        Ok(ident)
+    }
 }
 
 /// Extension for `syn::*Item::attrs` using `crate::ext::ItemLike`
@@ -262,12 +694,136 @@ impl ItemAttrExt for Item {}
 impl ItemAttrExt for ImplItem {}
 impl ItemAttrExt for TraitItem {}
 
-/// Extension for [syn::ItemMod]
-pub trait ItemModExt {
+/// Extension for [syn::ItemMod] and [syn::File]
+///
+/// Paths given to the lookup methods are relative to the content of self.
+/// `crate::` is regarded as self, and `self::`, `super::` are followed in nested inline modules.
+pub trait ItemModExt: Spanned {
     /// Returns reference of content items without braces unless a declaration
     fn items(&self) -> Option<&[Item]>;
     /// Returns reference of content items without braces unless a declaration
     fn items_mut(&mut self) -> Option<&mut Vec<Item>>;
+
+    /// Returns nested inline modules with their paths, in depth-first order
+    fn inline_mods(&self) -> Vec<(Vec<Ident>, &ItemMod)> {
+        self.items_by(|item| matches!(item, Item::Mod(m) if m.content.is_some()))
+            .into_iter()
+            .filter_map(|(mut path, item)| match item {
+                Item::Mod(module) => {
+                    path.push(module.ident.clone());
+                    Some((path, module))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the item of `path` through nested inline modules; `use` aliases are not followed.
+    fn find_item(&self, path: &Path) -> Option<&Item> {
+        let segments = path_idents(path);
+        let (last, init) = segments.split_last()?;
+        let items = module_items(self.items()?, &module_path(init)?)?;
+        named_item(items, last)
+    }
+
+    /// Returns the item of `path` through nested inline modules; `use` aliases are not followed.
+    fn find_item_mut(&mut self, path: &Path) -> Option<&mut Item> {
+        let segments = path_idents(path);
+        let (last, init) = segments.split_last()?;
+        let mut items = self.items_mut()?;
+        for name in module_path(init)? {
+            items = items.iter_mut().find_map(|item| match item {
+                Item::Mod(module) if module.ident == name => module.items_mut(),
+                _ => None,
+            })?;
+        }
+        items.iter_mut().find(|item| is_named(item, last))
+    }
+
+    /// Returns items matching `predicate` in self and nested inline modules with their module paths
+    fn items_by<F>(&self, mut predicate: F) -> Vec<(Vec<Ident>, &Item)>
+    where
+        F: FnMut(&Item) -> bool,
+    {
+        let mut found = Vec::new();
+        if let Some(items) = self.items() {
+            collect_items(items, &mut Vec::new(), &mut predicate, &mut found);
+        }
+        found
+    }
+
+    /// Returns the item of `path` with the path of its module, following `use` aliases and globs
+    /// which point into self
+    fn resolve_item(&self, path: &Path) -> Option<(Vec<Ident>, &Item)> {
+        resolve_in(
+            self.items()?,
+            Vec::new(),
+            &path_idents(path),
+            0,
+            &mut HashSet::new(),
+        )
+    }
+
+    /// Inserts `item` right before the item named `ident`; `Err` if not found
+    fn insert_item_before(&mut self, ident: &Ident, item: Item) -> Result<()> {
+        let items = items_or_err(self)?;
+        let index = position_or_err(items, ident)?;
+        items.insert(index, item);
+        Ok(())
+    }
+
+    /// Inserts `item` right after the item named `ident`; `Err` if not found
+    fn insert_item_after(&mut self, ident: &Ident, item: Item) -> Result<()> {
+        let items = items_or_err(self)?;
+        let index = position_or_err(items, ident)?;
+        items.insert(index + 1, item);
+        Ok(())
+    }
+
+    /// Inserts `item` after the leading `use` and `extern crate` items
+    fn insert_item_after_uses(&mut self, item: Item) -> Result<()> {
+        let items = items_or_err(self)?;
+        let index = items
+            .iter()
+            .position(|item| !matches!(item, Item::Use(_) | Item::ExternCrate(_)))
+            .unwrap_or(items.len());
+        items.insert(index, item);
+        Ok(())
+    }
+
+    /// Inserts `item` after the last item of the same kind; Otherwise same as [Self::push_item]
+    fn insert_item_grouped(&mut self, item: Item) -> Result<()> {
+        let items = items_or_err(self)?;
+        let kind = std::mem::discriminant(&item);
+        match items
+            .iter()
+            .rposition(|other| std::mem::discriminant(other) == kind && !is_test_mod(other))
+        {
+            Some(index) => items.insert(index + 1, item),
+            None => push_before_test_mods(items, item),
+        }
+        Ok(())
+    }
+
+    /// Appends `item` to the end but keeps trailing `#[cfg(test)]` modules last
+    fn push_item(&mut self, item: Item) -> Result<()> {
+        push_before_test_mods(items_or_err(self)?, item);
+        Ok(())
+    }
+
+    /// Removes and returns the item named `ident`; `Err` if not found
+    fn remove_item(&mut self, ident: &Ident) -> Result<Item> {
+        let items = items_or_err(self)?;
+        let index = position_or_err(items, ident)?;
+        Ok(items.remove(index))
+    }
+
+    /// Replaces the item named `ident` with `item` and returns the old one; `Err` if not found
+    fn replace_item(&mut self, ident: &Ident, item: Item) -> Result<Item> {
+        let items = items_or_err(self)?;
+        let index = position_or_err(items, ident)?;
+        Ok(std::mem::replace(&mut items[index], item))
+    }
 }
 
 impl ItemModExt for ItemMod {
@@ -287,55 +843,271 @@ impl ItemModExt for ItemMod {
     }
 }
 
-impl GetIdent for Item {
-    fn get_ident(&self) -> Option<&Ident> {
-        use syn::Item::*;
-        use syn::UseTree::*;
-        use syn::*;
-        #[allow(clippy::collapsible_match)]
-        let attrs = match self {
-            Const(ItemConst { ref ident, .. }) => ident,
-            Enum(ItemEnum { ref ident, .. }) => ident,
-            ExternCrate(ItemExternCrate { ref ident, .. }) => ident,
-            Fn(ItemFn { sig, .. }) => &sig.ident,
-            Impl(ItemImpl { .. }) => unimplemented!(),
-            Macro(ItemMacro { ref ident, .. }) => return ident.as_ref(),
-            Mod(ItemMod { ref ident, .. }) => ident,
-            Static(ItemStatic { ref ident, .. }) => ident,
-            Struct(ItemStruct { ref ident, .. }) => ident,
-            Trait(ItemTrait { ref ident, .. }) => ident,
-            TraitAlias(ItemTraitAlias { ref ident, .. }) => ident,
-            Type(ItemType { ref ident, .. }) => ident,
-            Union(ItemUnion { ref ident, .. }) => ident,
-            Use(ItemUse { ref tree, .. }) => match tree {
-                Name(UseName { ident }) => ident,
-                _ => return None,
-            },
-            _ => return None,
-        };
-        Some(attrs)
+impl ItemModExt for File {
+    fn items(&self) -> Option<&[Item]> {
+        Some(&self.items)
+    }
+    fn items_mut(&mut self) -> Option<&mut Vec<Item>> {
+        Some(&mut self.items)
     }
 }
 
-impl GetIdent for ImplItem {
-    fn get_ident(&self) -> Option<&Ident> {
-        use syn::ImplItem::*;
-        use syn::*;
-        let ident = match self {
-            Const(ImplItemConst { ref ident, .. }) => ident,
-            Fn(ImplItemFn { sig, .. }) => &sig.ident,
-            Type(ImplItemType { ref ident, .. }) => ident,
-            Macro(ImplItemMacro {
-                mac: syn::Macro { path, .. },
-                ..
-            }) => return path.get_ident(),
-            _ => return None,
-        };
-        Some(ident)
+fn items_or_err<M>(module: &mut M) -> Result<&mut Vec<Item>>
+where
+    M: ItemModExt + ?Sized,
+{
+    let span = module.span();
+    module
+        .items_mut()
+        .ok_or_else(|| Error::new(span, "module declaration doesn't have items"))
+}
+
+fn position_or_err(items: &[Item], ident: &Ident) -> Result<usize> {
+    items
+        .iter()
+        .position(|item| is_named(item, ident))
+        .ok_or_else(|| {
+            Error::new(
+                ident.span(),
+                format!("cannot find item `{}` in this module", ident),
+            )
+        })
+}
+
+/// Returns `true` if the module has `#[cfg(..)]` which only holds under `test`,
+/// like `#[cfg(test)]` or `#[cfg(all(test, unix))]`
+pub(crate) fn is_cfg_test(module: &ItemMod) -> bool {
+    module.attrs.iter().any(|attr| match &attr.meta {
+        syn::Meta::List(list) => list.path.is_ident("cfg") && requires_test(list.tokens.clone()),
+        _ => false,
+    })
+}
+
+/// Returns `true` if the cfg predicate of `tokens` can't hold without `test`.
+///
+/// `not(..)` is never considered to require `test`.
+fn requires_test(tokens: TokenStream) -> bool {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    match tokens.as_slice() {
+        [TokenTree::Ident(ident)] => ident == "test",
+        [TokenTree::Ident(ident), TokenTree::Group(group)]
+            if group.delimiter() == Delimiter::Parenthesis =>
+        {
+            let mut predicates = split_predicates(group.stream()).into_iter();
+            if ident == "all" {
+                predicates.any(requires_test)
+            } else if ident == "any" {
+                predicates.len() > 0 && predicates.all(requires_test)
+            } else {
+                false
+            }
+        }
+        _ => false,
     }
 }
 
-impl GetIdent for TraitItem {
+/// Splits comma separated cfg predicates
+fn split_predicates(tokens: TokenStream) -> Vec<TokenStream> {
+    let mut predicates = Vec::new();
+    let mut current = TokenStream::new();
+    for token in tokens {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                predicates.push(std::mem::take(&mut current));
+            }
+            _ => current.extend(Some(token)),
+        }
+    }
+    if !current.is_empty() {
+        predicates.push(current);
+    }
+    predicates
+}
+
+/// Returns `true` if the item is `#[cfg(test)] mod`
+fn is_test_mod(item: &Item) -> bool {
+    match item {
+        Item::Mod(module) => is_cfg_test(module),
+        _ => false,
+    }
+}
+
+fn push_before_test_mods(items: &mut Vec<Item>, item: Item) {
+    let index = items
+        .iter()
+        .rposition(|item| !is_test_mod(item))
+        .map_or(0, |index| index + 1);
+    items.insert(index, item);
+}
+
+/// Limit of `use` aliases to be followed by [ItemModExt::resolve_item]; prevents alias cycles
+const MAX_ALIAS_DEPTH: usize = 32;
+
+fn path_idents(path: &Path) -> Vec<Ident> {
+    path.segments.iter().map(|s| s.ident.clone()).collect()
+}
+
+/// Converts relative path segments to a module path from the root, without aliases
+fn module_path(segments: &[Ident]) -> Option<Vec<Ident>> {
+    let mut path = Vec::new();
+    for segment in segments {
+        if segment == "crate" {
+            path.clear();
+        } else if segment == "super" {
+            path.pop()?;
+        } else if segment != "self" {
+            path.push(segment.clone());
+        }
+    }
+    Some(path)
+}
+
+fn is_named(item: &Item, name: &Ident) -> bool {
+    // impl doesn't have a name and use is an alias rather than the item
+    !matches!(item, Item::Use(_) | Item::Impl(_)) && item.get_ident() == Some(name)
+}
+
+fn named_item<'a>(items: &'a [Item], name: &Ident) -> Option<&'a Item> {
+    items.iter().find(|item| is_named(item, name))
+}
+
+fn module_items<'a>(root: &'a [Item], path: &[Ident]) -> Option<&'a [Item]> {
+    path.iter().try_fold(root, |items, name| {
+        items.iter().find_map(|item| match item {
+            Item::Mod(module) if module.ident == *name => module.items(),
+            _ => None,
+        })
+    })
+}
+
+pub(crate) fn collect_items<'a, F>(
+    items: &'a [Item],
+    path: &mut Vec<Ident>,
+    predicate: &mut F,
+    found: &mut Vec<(Vec<Ident>, &'a Item)>,
+) where
+    F: FnMut(&Item) -> bool,
+{
+    for item in items {
+        if predicate(item) {
+            found.push((path.clone(), item));
+        }
+        if let Item::Mod(module) = item {
+            if let Some(content) = module.items() {
+                path.push(module.ident.clone());
+                collect_items(content, path, predicate, found);
+                path.pop();
+            }
+        }
+    }
+}
+
+fn use_entries(items: &[Item]) -> impl Iterator<Item = UseEntry> + '_ {
+    items.iter().flat_map(|item| match item {
+        Item::Use(item) => item.flatten(),
+        _ => Vec::new(),
+    })
+}
+
+/// Resolves `segments` from `module`. `visited` holds `(module, name)` pairs already looked up,
+/// which ends cycles of glob imports.
+fn resolve_in<'a>(
+    root: &'a [Item],
+    mut module: Vec<Ident>,
+    segments: &[Ident],
+    depth: usize,
+    visited: &mut HashSet<(Vec<Ident>, Ident)>,
+) -> Option<(Vec<Ident>, &'a Item)> {
+    if depth > MAX_ALIAS_DEPTH {
+        return None;
+    }
+    let (last, init) = segments.split_last()?;
+    for (i, segment) in init.iter().enumerate() {
+        if segment == "crate" {
+            module.clear();
+            continue;
+        } else if segment == "super" {
+            module.pop()?;
+            continue;
+        } else if segment == "self" {
+            continue;
+        }
+        let items = module_items(root, &module)?;
+        if module_items(items, std::slice::from_ref(segment)).is_some() {
+            module.push(segment.clone());
+            continue;
+        }
+        let entry = use_entries(items).find(|e| e.get_ident() == Some(segment))?;
+        let mut segments = entry.segments;
+        segments.extend_from_slice(&init[i + 1..]);
+        segments.push(last.clone());
+        return resolve_in(root, module, &segments, depth + 1, visited);
+    }
+    if !visited.insert((module.clone(), last.clone())) {
+        return None;
+    }
+    let items = module_items(root, &module)?;
+    if let Some(item) = named_item(items, last) {
+        return Some((module, item));
+    }
+    let mut globs = Vec::new();
+    for entry in use_entries(items) {
+        if entry.glob {
+            globs.push(entry.segments);
+        } else if entry.get_ident() == Some(last) {
+            return resolve_in(root, module, &entry.segments, depth + 1, visited);
+        }
+    }
+    globs.into_iter().find_map(|mut segments| {
+        segments.push(last.clone());
+        resolve_in(root, module.clone(), &segments, depth + 1, visited)
+    })
+}
+
+impl GetIdent for Item {
+    fn get_ident(&self) -> Option<&Ident> {
+        use syn::Item::*;
+        use syn::*;
+        let attrs = match self {
+            Const(ItemConst { ref ident, .. }) => ident,
+            Enum(ItemEnum { ref ident, .. }) => ident,
+            ExternCrate(ItemExternCrate { ref ident, .. }) => ident,
+            Fn(ItemFn { sig, .. }) => &sig.ident,
+            Impl(ItemImpl { .. }) => unimplemented!(),
+            Macro(ItemMacro { ref ident, .. }) => return ident.as_ref(),
+            Mod(ItemMod { ref ident, .. }) => ident,
+            Static(ItemStatic { ref ident, .. }) => ident,
+            Struct(ItemStruct { ref ident, .. }) => ident,
+            Trait(ItemTrait { ref ident, .. }) => ident,
+            TraitAlias(ItemTraitAlias { ref ident, .. }) => ident,
+            Type(ItemType { ref ident, .. }) => ident,
+            Union(ItemUnion { ref ident, .. }) => ident,
+            Use(ItemUse { ref tree, .. }) => return use_tree_ident(tree),
+            _ => return None,
+        };
+        Some(attrs)
+    }
+}
+
+impl GetIdent for ImplItem {
+    fn get_ident(&self) -> Option<&Ident> {
+        use syn::ImplItem::*;
+        use syn::*;
+        let ident = match self {
+            Const(ImplItemConst { ref ident, .. }) => ident,
+            Fn(ImplItemFn { sig, .. }) => &sig.ident,
+            Type(ImplItemType { ref ident, .. }) => ident,
+            Macro(ImplItemMacro {
+                mac: syn::Macro { path, .. },
+                ..
+            }) => return path.get_ident(),
+            _ => return None,
+        };
+        Some(ident)
+    }
+}
+
+impl GetIdent for TraitItem {
     fn get_ident(&self) -> Option<&Ident> {
         use syn::TraitItem::*;
         use syn::*;
@@ -353,7 +1125,7 @@ impl GetIdent for TraitItem {
     }
 }
 
-/// Extension for [syn::ItemFn], [syn::ImplItemMethod], and [syn::TraitItemFn]
+/// Extension for [syn::ItemFn], [syn::ImplItemFn], [syn::TraitItemFn], and [syn::ForeignItemFn]
 pub trait FunctionLike: Spanned {
     /// Returns reference of attrs
     fn attrs(&self) -> &[Attribute];
@@ -364,6 +1136,36 @@ pub trait FunctionLike: Spanned {
 
     fn sig(&self) -> &syn::Signature;
     fn block(&self) -> Option<&syn::Block>;
+
+    /// Returns `true` if the function has `where Self: Sized`
+    fn has_sized_escape(&self) -> bool {
+        self.sig()
+            .generics
+            .where_clause
+            .as_ref()
+            .is_some_and(has_self_sized)
+    }
+}
+
+/// Returns `true` if `where_clause` has `Self: Sized`
+pub(crate) fn has_self_sized(where_clause: &syn::WhereClause) -> bool {
+    where_clause.predicates.iter().any(|predicate| match predicate {
+        syn::WherePredicate::Type(predicate) => {
+            matches!(&predicate.bounded_ty, syn::Type::Path(ty) if ty.qself.is_none() && ty.path.is_ident("Self"))
+                && predicate.bounds.iter().any(|bound| match bound {
+                    syn::TypeParamBound::Trait(bound) => {
+                        matches!(bound.modifier, syn::TraitBoundModifier::None)
+                            && bound
+                                .path
+                                .segments
+                                .last()
+                                .is_some_and(|segment| segment.ident == "Sized")
+                    }
+                    _ => false,
+                })
+        }
+        _ => false,
+    })
 }
 
 impl FunctionLike for ItemFn {
@@ -420,6 +1222,24 @@ impl FunctionLike for TraitItemFn {
     }
 }
 
+impl FunctionLike for ForeignItemFn {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn attrs_mut(&mut self) -> &mut Vec<Attribute> {
+        &mut self.attrs
+    }
+    fn vis(&self) -> &syn::Visibility {
+        &self.vis
+    }
+    fn sig(&self) -> &syn::Signature {
+        &self.sig
+    }
+    fn block(&self) -> Option<&syn::Block> {
+        None
+    }
+}
+
 /// Extension for [syn::ItemConst], [syn::ImplItemConst], and [syn::TraitItemConst]
 pub trait ConstLike: Spanned {
     /// Returns reference of attrs
@@ -436,6 +1256,8 @@ pub trait ConstLike: Spanned {
     fn colon_token(&self) -> &syn::token::Colon;
     /// Return reference of ty
     fn ty(&self) -> &syn::Type;
+    /// Return reference of the value, or the default of an associated const of a trait
+    fn expr(&self) -> Option<&syn::Expr>;
 }
 
 impl ConstLike for syn::ItemConst {
@@ -460,6 +1282,9 @@ impl ConstLike for syn::ItemConst {
     fn ty(&self) -> &syn::Type {
         &self.ty
     }
+    fn expr(&self) -> Option<&syn::Expr> {
+        Some(&self.expr)
+    }
 }
 
 impl ConstLike for syn::ImplItemConst {
@@ -484,6 +1309,9 @@ impl ConstLike for syn::ImplItemConst {
     fn ty(&self) -> &syn::Type {
         &self.ty
     }
+    fn expr(&self) -> Option<&syn::Expr> {
+        Some(&self.expr)
+    }
 }
 
 impl ConstLike for syn::TraitItemConst {
@@ -508,6 +1336,275 @@ impl ConstLike for syn::TraitItemConst {
     fn ty(&self) -> &syn::Type {
         &self.ty
     }
+    fn expr(&self) -> Option<&syn::Expr> {
+        self.default.as_ref().map(|(_, expr)| expr)
+    }
+}
+
+/// Extension for [syn::ItemType], [syn::ImplItemType], [syn::TraitItemType], and [syn::ForeignItemType]
+pub trait TypeLike: Spanned {
+    /// Returns reference of attrs
+    fn attrs(&self) -> &[Attribute];
+    /// Returns mutable reference of attrs
+    fn attrs_mut(&mut self) -> &mut Vec<Attribute>;
+    /// Return reference of vis
+    fn vis(&self) -> &syn::Visibility;
+    /// Return reference of ident
+    fn ident(&self) -> &syn::Ident;
+    /// Return reference of generics
+    fn generics(&self) -> &syn::Generics;
+    /// Return reference of bounds if the type is an associated type of a trait
+    fn bounds(&self) -> Option<&Punctuated<syn::TypeParamBound, syn::token::Plus>>;
+    /// Return reference of the aliased type, or the default of an associated type of a trait
+    fn ty(&self) -> Option<&syn::Type>;
+}
+
+impl TypeLike for syn::ItemType {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn attrs_mut(&mut self) -> &mut Vec<Attribute> {
+        &mut self.attrs
+    }
+    fn vis(&self) -> &syn::Visibility {
+        &self.vis
+    }
+    fn ident(&self) -> &syn::Ident {
+        &self.ident
+    }
+    fn generics(&self) -> &syn::Generics {
+        &self.generics
+    }
+    fn bounds(&self) -> Option<&Punctuated<syn::TypeParamBound, syn::token::Plus>> {
+        None
+    }
+    fn ty(&self) -> Option<&syn::Type> {
+        Some(&self.ty)
+    }
+}
+
+impl TypeLike for syn::ImplItemType {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn attrs_mut(&mut self) -> &mut Vec<Attribute> {
+        &mut self.attrs
+    }
+    fn vis(&self) -> &syn::Visibility {
+        &self.vis
+    }
+    fn ident(&self) -> &syn::Ident {
+        &self.ident
+    }
+    fn generics(&self) -> &syn::Generics {
+        &self.generics
+    }
+    fn bounds(&self) -> Option<&Punctuated<syn::TypeParamBound, syn::token::Plus>> {
+        None
+    }
+    fn ty(&self) -> Option<&syn::Type> {
+        Some(&self.ty)
+    }
+}
+
+impl TypeLike for syn::TraitItemType {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn attrs_mut(&mut self) -> &mut Vec<Attribute> {
+        &mut self.attrs
+    }
+    fn vis(&self) -> &syn::Visibility {
+        &syn::Visibility::Inherited
+    }
+    fn ident(&self) -> &syn::Ident {
+        &self.ident
+    }
+    fn generics(&self) -> &syn::Generics {
+        &self.generics
+    }
+    fn bounds(&self) -> Option<&Punctuated<syn::TypeParamBound, syn::token::Plus>> {
+        Some(&self.bounds)
+    }
+    fn ty(&self) -> Option<&syn::Type> {
+        self.default.as_ref().map(|(_, ty)| ty)
+    }
+}
+
+impl TypeLike for syn::ForeignItemType {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn attrs_mut(&mut self) -> &mut Vec<Attribute> {
+        &mut self.attrs
+    }
+    fn vis(&self) -> &syn::Visibility {
+        &self.vis
+    }
+    fn ident(&self) -> &syn::Ident {
+        &self.ident
+    }
+    fn generics(&self) -> &syn::Generics {
+        &self.generics
+    }
+    fn bounds(&self) -> Option<&Punctuated<syn::TypeParamBound, syn::token::Plus>> {
+        None
+    }
+    fn ty(&self) -> Option<&syn::Type> {
+        None
+    }
+}
+
+/// Extension for [syn::ItemMacro], [syn::ImplItemMacro], [syn::TraitItemMacro], and [syn::ForeignItemMacro]
+pub trait MacroLike: Spanned {
+    /// Returns reference of attrs
+    fn attrs(&self) -> &[Attribute];
+    /// Returns mutable reference of attrs
+    fn attrs_mut(&mut self) -> &mut Vec<Attribute>;
+    /// Return reference of the macro invocation
+    fn mac(&self) -> &syn::Macro;
+    /// Return reference of the defined name of `macro_rules! name { ... }`
+    fn ident(&self) -> Option<&syn::Ident>;
+    /// Return reference of semi_token
+    fn semi_token(&self) -> Option<&syn::token::Semi>;
+}
+
+impl MacroLike for syn::ItemMacro {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn attrs_mut(&mut self) -> &mut Vec<Attribute> {
+        &mut self.attrs
+    }
+    fn mac(&self) -> &syn::Macro {
+        &self.mac
+    }
+    fn ident(&self) -> Option<&syn::Ident> {
+        self.ident.as_ref()
+    }
+    fn semi_token(&self) -> Option<&syn::token::Semi> {
+        self.semi_token.as_ref()
+    }
+}
+
+impl MacroLike for syn::ImplItemMacro {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn attrs_mut(&mut self) -> &mut Vec<Attribute> {
+        &mut self.attrs
+    }
+    fn mac(&self) -> &syn::Macro {
+        &self.mac
+    }
+    fn ident(&self) -> Option<&syn::Ident> {
+        None
+    }
+    fn semi_token(&self) -> Option<&syn::token::Semi> {
+        self.semi_token.as_ref()
+    }
+}
+
+impl MacroLike for syn::TraitItemMacro {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn attrs_mut(&mut self) -> &mut Vec<Attribute> {
+        &mut self.attrs
+    }
+    fn mac(&self) -> &syn::Macro {
+        &self.mac
+    }
+    fn ident(&self) -> Option<&syn::Ident> {
+        None
+    }
+    fn semi_token(&self) -> Option<&syn::token::Semi> {
+        self.semi_token.as_ref()
+    }
+}
+
+impl MacroLike for syn::ForeignItemMacro {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn attrs_mut(&mut self) -> &mut Vec<Attribute> {
+        &mut self.attrs
+    }
+    fn mac(&self) -> &syn::Macro {
+        &self.mac
+    }
+    fn ident(&self) -> Option<&syn::Ident> {
+        None
+    }
+    fn semi_token(&self) -> Option<&syn::token::Semi> {
+        self.semi_token.as_ref()
+    }
+}
+
+/// Extension for [syn::ItemStatic] and [syn::ForeignItemStatic]
+pub trait StaticLike: Spanned {
+    /// Returns reference of attrs
+    fn attrs(&self) -> &[Attribute];
+    /// Returns mutable reference of attrs
+    fn attrs_mut(&mut self) -> &mut Vec<Attribute>;
+    /// Return reference of vis
+    fn vis(&self) -> &syn::Visibility;
+    /// Return reference of mutability
+    fn mutability(&self) -> &syn::StaticMutability;
+    /// Return reference of ident
+    fn ident(&self) -> &syn::Ident;
+    /// Return reference of ty
+    fn ty(&self) -> &syn::Type;
+    /// Return reference of the initializer; foreign statics don't have one
+    fn expr(&self) -> Option<&syn::Expr>;
+}
+
+impl StaticLike for syn::ItemStatic {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn attrs_mut(&mut self) -> &mut Vec<Attribute> {
+        &mut self.attrs
+    }
+    fn vis(&self) -> &syn::Visibility {
+        &self.vis
+    }
+    fn mutability(&self) -> &syn::StaticMutability {
+        &self.mutability
+    }
+    fn ident(&self) -> &syn::Ident {
+        &self.ident
+    }
+    fn ty(&self) -> &syn::Type {
+        &self.ty
+    }
+    fn expr(&self) -> Option<&syn::Expr> {
+        Some(&self.expr)
+    }
+}
+
+impl StaticLike for syn::ForeignItemStatic {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn attrs_mut(&mut self) -> &mut Vec<Attribute> {
+        &mut self.attrs
+    }
+    fn vis(&self) -> &syn::Visibility {
+        &self.vis
+    }
+    fn mutability(&self) -> &syn::StaticMutability {
+        &self.mutability
+    }
+    fn ident(&self) -> &syn::Ident {
+        &self.ident
+    }
+    fn ty(&self) -> &syn::Type {
+        &self.ty
+    }
+    fn expr(&self) -> Option<&syn::Expr> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -534,6 +1631,80 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_type_macro_static() {
+        let item: TraitItem = parse_quote! {
+            type A: Clone + Send = u8;
+        };
+        let ty = item.type_alias().unwrap();
+        assert_eq!(ty.ident(), "A");
+        assert_quote_eq!(ty.bounds().unwrap(), quote!(Clone + Send));
+        assert_quote_eq!(ty.ty().unwrap(), quote!(u8));
+        assert!(item.macro_call().is_err());
+
+        let item: Item = parse_quote! {
+            macro_rules! m { () => {} }
+        };
+        let mac = item.macro_call().unwrap();
+        assert_eq!(mac.ident().unwrap(), "m");
+        assert!(mac.mac().path.is_ident("macro_rules"));
+
+        let item: ForeignItem = parse_quote! {
+            static mut A: u8;
+        };
+        let st = item.static_item().unwrap();
+        assert!(matches!(st.mutability(), syn::StaticMutability::Mut(_)));
+        assert!(st.expr().is_none());
+        assert!(item.type_alias().is_err());
+
+        let item: ForeignItem = parse_quote! {
+            pub type Opaque;
+        };
+        assert!(item.is_type());
+        let ty = item.type_alias().unwrap();
+        assert_eq!(ty.ident(), "Opaque");
+        assert!(ty.bounds().is_none());
+        assert!(ty.ty().is_none());
+    }
+
+    #[test]
+    fn test_vis_generics_ident() {
+        let mut item: Item = parse_quote! {
+            fn f<T>(t: T) {}
+        };
+        *item.vis_mut().unwrap() = parse_quote!(pub(crate));
+        item.generics_mut()
+            .unwrap()
+            .params
+            .push(parse_quote!(U: Clone));
+        let old = item.set_ident(parse_quote!(g)).unwrap();
+        assert_eq!(old, "f");
+        assert_quote_eq!(item.generics().unwrap().params, quote!(T, U: Clone));
+        let expected: Item = parse_quote! {
+            pub(crate) fn g<T, U: Clone>(t: T) {}
+        };
+        assert_quote_eq!(item, expected);
+
+        let mut item: Item = parse_quote! {
+            impl A {}
+        };
+        assert!(item.vis().is_err());
+        assert!(item.ident_mut().is_err());
+        assert!(item.generics().is_ok());
+
+        let mut item: TraitItem = parse_quote! {
+            type A<T>;
+        };
+        assert!(item.vis_mut().is_err());
+        item.set_ident(parse_quote!(B)).unwrap();
+        assert_quote_eq!(
+            item,
+            quote!(
+                type B<T>;
+            )
+        );
+    }
+
     #[test]
     fn test_items() {
         let module: ItemMod = parse_quote!(
@@ -555,6 +1726,226 @@ mod tests {
         assert!(module.items().is_none());
     }
 
+    #[test]
+    fn test_find_item() {
+        let mut module: ItemMod = parse_quote! {
+            mod m {
+                struct A;
+                mod a {
+                    mod b {
+                        struct Foo;
+                        impl Foo {}
+                    }
+                    use self::b::Foo;
+                }
+            }
+        };
+        let found = module.find_item(&parse_quote!(a::b::Foo)).unwrap();
+        assert!(matches!(found, Item::Struct(_)));
+        assert!(module.find_item(&parse_quote!(crate::A)).is_some());
+        assert!(module.find_item(&parse_quote!(a::Foo)).is_none());
+        assert!(module.find_item(&parse_quote!(a::c::Foo)).is_none());
+
+        let found = module.find_item_mut(&parse_quote!(a::b::Foo)).unwrap();
+        *found = parse_quote!(
+            enum Foo {}
+        );
+        let found = module.find_item(&parse_quote!(a::b::Foo)).unwrap();
+        assert!(matches!(found, Item::Enum(_)));
+    }
+
+    #[test]
+    fn test_inline_mods_and_items_by() {
+        let module: ItemMod = parse_quote! {
+            mod m {
+                struct A;
+                mod a {
+                    mod b {
+                        struct B;
+                    }
+                    mod c;
+                }
+            }
+        };
+        let mods: Vec<_> = module
+            .inline_mods()
+            .into_iter()
+            .map(|(path, _)| quote!(#(#path)::*).to_string())
+            .collect();
+        assert_eq!(mods, vec!["a", "a :: b"]);
+
+        let structs: Vec<_> = module
+            .items_by(|item| matches!(item, Item::Struct(_)))
+            .into_iter()
+            .map(|(path, item)| {
+                let ident = item.get_ident().unwrap();
+                quote!(#(#path::)* #ident).to_string()
+            })
+            .collect();
+        assert_eq!(structs, vec!["A", "a :: b :: B"]);
+    }
+
+    #[test]
+    fn test_resolve_item() {
+        let module: ItemMod = parse_quote! {
+            mod m {
+                mod a {
+                    pub struct Foo;
+                }
+                mod b {
+                    pub use super::a::Foo as Bar;
+                    pub use crate::a as alias;
+                    pub use crate::c::*;
+                }
+                mod c {
+                    pub fn f() {}
+                }
+                use self::b::Bar as Baz;
+                use self::Cycle as Cycle2;
+                use self::Cycle2 as Cycle;
+            }
+        };
+        let (path, item) = module.resolve_item(&parse_quote!(Baz)).unwrap();
+        assert_eq!(quote!(#(#path)::*).to_string(), "a");
+        assert_eq!(item.get_ident().unwrap(), "Foo");
+        let (path, _) = module.resolve_item(&parse_quote!(b::alias::Foo)).unwrap();
+        assert_eq!(quote!(#(#path)::*).to_string(), "a");
+        let (path, item) = module.resolve_item(&parse_quote!(b::f)).unwrap();
+        assert_eq!(quote!(#(#path)::*).to_string(), "c");
+        assert!(matches!(item, Item::Fn(_)));
+        assert!(module.resolve_item(&parse_quote!(Cycle)).is_none());
+        assert!(module.resolve_item(&parse_quote!(b::Unknown)).is_none());
+
+        let module: ItemMod = parse_quote! {
+            mod m {
+                mod a {
+                    pub use super::b::*;
+                    pub use super::c::*;
+                }
+                mod b {
+                    pub use super::a::*;
+                    pub use super::c::*;
+                }
+                mod c {
+                    pub use super::a::*;
+                    pub use super::b::*;
+                    pub struct Found;
+                }
+            }
+        };
+        assert!(module.resolve_item(&parse_quote!(a::Missing)).is_none());
+        let (path, _) = module.resolve_item(&parse_quote!(a::Found)).unwrap();
+        assert_eq!(quote!(#(#path)::*).to_string(), "c");
+    }
+
+    #[test]
+    fn test_insert_items() {
+        let mut file: File = parse_quote! {
+            use a::b;
+            struct A;
+            fn f() {}
+            #[cfg(test)]
+            mod tests {}
+        };
+        let a: Ident = parse_quote!(A);
+        file.insert_item_before(
+            &a,
+            parse_quote!(
+                struct B;
+            ),
+        )
+        .unwrap();
+        file.insert_item_after(
+            &a,
+            parse_quote!(
+                struct C;
+            ),
+        )
+        .unwrap();
+        file.insert_item_after_uses(parse_quote!(
+            const X: u8 = 0;
+        ))
+        .unwrap();
+        file.insert_item_grouped(parse_quote!(
+            use c::d;
+        ))
+        .unwrap();
+        file.insert_item_grouped(parse_quote!(
+            static Y: u8 = 0;
+        ))
+        .unwrap();
+        file.push_item(parse_quote!(
+            fn g() {}
+        ))
+        .unwrap();
+        let expected: File = parse_quote! {
+            use a::b;
+            use c::d;
+            const X: u8 = 0;
+            struct B;
+            struct A;
+            struct C;
+            fn f() {}
+            static Y: u8 = 0;
+            fn g() {}
+            #[cfg(test)]
+            mod tests {}
+        };
+        assert_quote_eq!(file, expected);
+
+        let unknown: Ident = parse_quote!(Unknown);
+        let err = file
+            .insert_item_after(
+                &unknown,
+                parse_quote!(
+                    struct D;
+                ),
+            )
+            .unwrap_err();
+        assert_eq!(err.to_string(), "cannot find item `Unknown` in this module");
+    }
+
+    #[test]
+    fn test_remove_replace_item() {
+        let mut module: ItemMod = parse_quote! {
+            mod m {
+                struct A;
+                fn f() {}
+            }
+        };
+        let f: Ident = parse_quote!(f);
+        let old = module
+            .replace_item(
+                &f,
+                parse_quote!(
+                    fn f(x: u8) {}
+                ),
+            )
+            .unwrap();
+        assert_quote_eq!(
+            old,
+            quote!(
+                fn f() {}
+            )
+        );
+        let a: Ident = parse_quote!(A);
+        module.remove_item(&a).unwrap();
+        assert!(module.remove_item(&a).is_err());
+        let expected: ItemMod = parse_quote! {
+            mod m {
+                fn f(x: u8) {}
+            }
+        };
+        assert_quote_eq!(module, expected);
+
+        let mut decl: ItemMod = parse_quote! { mod m; };
+        assert!(decl
+            .push_item(parse_quote!(
+                struct A;
+            ))
+            .is_err());
+    }
+
     #[test]
     fn test_function_like() {
         let function: ItemFn = parse_quote!(
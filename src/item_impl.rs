@@ -0,0 +1,199 @@
+use crate::ident::GetIdent;
+use crate::item::{FunctionLike, ItemLike};
+use syn::{
+    Error, Ident, ImplItem, ImplItemConst, ImplItemFn, ImplItemType, ItemImpl, Path, Result, Type,
+};
+
+/// Namespace of associated items; consts and functions share the value namespace
+fn is_type_namespace(item: &ImplItem) -> bool {
+    item.is_type()
+}
+
+/// Extension for [syn::ItemImpl]
+pub trait ItemImplExt {
+    /// Returns the method or associated function named `name`
+    fn find_fn<N>(&self, name: &N) -> Option<&ImplItemFn>
+    where
+        N: ?Sized,
+        Ident: PartialEq<N>;
+    /// Returns the method or associated function named `name` as mutable
+    fn find_fn_mut<N>(&mut self, name: &N) -> Option<&mut ImplItemFn>
+    where
+        N: ?Sized,
+        Ident: PartialEq<N>;
+    /// Returns the associated const named `name`
+    fn find_const<N>(&self, name: &N) -> Option<&ImplItemConst>
+    where
+        N: ?Sized,
+        Ident: PartialEq<N>;
+    /// Returns the associated type named `name`
+    fn find_type<N>(&self, name: &N) -> Option<&ImplItemType>
+    where
+        N: ?Sized,
+        Ident: PartialEq<N>;
+
+    /// Pushes `item` unless an item of the same name exists in its namespace.
+    ///
+    /// Associated consts and functions share a namespace; associated types have their own.
+    /// Macro invocations are always pushed.
+    fn add_item(&mut self, item: ImplItem) -> Result<()>;
+
+    /// Returns every method and associated function
+    fn fns(&self) -> Vec<&dyn FunctionLike>;
+
+    /// Returns `true` for `impl Trait for Type`
+    fn is_trait_impl(&self) -> bool;
+    /// Returns `true` for `impl !Trait for Type`
+    fn is_negative(&self) -> bool;
+    /// Returns the path of the implemented trait
+    fn trait_path(&self) -> Option<&Path>;
+    /// Returns the path of the self type if it is a plain path like `a::B<T>`
+    fn self_ty_path(&self) -> Option<&Path>;
+}
+
+impl ItemImplExt for ItemImpl {
+    fn find_fn<N>(&self, name: &N) -> Option<&ImplItemFn>
+    where
+        N: ?Sized,
+        Ident: PartialEq<N>,
+    {
+        self.items.iter().find_map(|item| match item {
+            ImplItem::Fn(f) if f.sig.ident == *name => Some(f),
+            _ => None,
+        })
+    }
+
+    fn find_fn_mut<N>(&mut self, name: &N) -> Option<&mut ImplItemFn>
+    where
+        N: ?Sized,
+        Ident: PartialEq<N>,
+    {
+        self.items.iter_mut().find_map(|item| match item {
+            ImplItem::Fn(f) if f.sig.ident == *name => Some(f),
+            _ => None,
+        })
+    }
+
+    fn find_const<N>(&self, name: &N) -> Option<&ImplItemConst>
+    where
+        N: ?Sized,
+        Ident: PartialEq<N>,
+    {
+        self.items.iter().find_map(|item| match item {
+            ImplItem::Const(c) if c.ident == *name => Some(c),
+            _ => None,
+        })
+    }
+
+    fn find_type<N>(&self, name: &N) -> Option<&ImplItemType>
+    where
+        N: ?Sized,
+        Ident: PartialEq<N>,
+    {
+        self.items.iter().find_map(|item| match item {
+            ImplItem::Type(t) if t.ident == *name => Some(t),
+            _ => None,
+        })
+    }
+
+    fn add_item(&mut self, item: ImplItem) -> Result<()> {
+        if !item.is_macro() {
+            if let Some(ident) = item.get_ident() {
+                let duplicated = self.items.iter().any(|other| {
+                    !other.is_macro()
+                        && is_type_namespace(other) == is_type_namespace(&item)
+                        && other.get_ident() == Some(ident)
+                });
+                if duplicated {
+                    return Err(Error::new_spanned(
+                        ident,
+                        format!("duplicate definitions with name `{}`", ident),
+                    ));
+                }
+            }
+        }
+        self.items.push(item);
+        Ok(())
+    }
+
+    fn fns(&self) -> Vec<&dyn FunctionLike> {
+        self.items
+            .iter()
+            .filter_map(|item| item.function_or_method().ok())
+            .collect()
+    }
+
+    fn is_trait_impl(&self) -> bool {
+        self.trait_.is_some()
+    }
+
+    fn is_negative(&self) -> bool {
+        matches!(self.trait_, Some((Some(_), _, _)))
+    }
+
+    fn trait_path(&self) -> Option<&Path> {
+        self.trait_.as_ref().map(|(_, path, _)| path)
+    }
+
+    fn self_ty_path(&self) -> Option<&Path> {
+        match &*self.self_ty {
+            Type::Path(ty) if ty.qself.is_none() => Some(&ty.path),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_quote_eq;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_find() {
+        let mut item: ItemImpl = parse_quote! {
+            impl<T> fmt::Debug for a::A<T> {
+                type Output = ();
+                const N: usize = 0;
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { Ok(()) }
+                fn new() -> Self { todo!() }
+            }
+        };
+        assert!(item.is_trait_impl());
+        assert!(!item.is_negative());
+        assert_quote_eq!(item.trait_path().unwrap(), quote!(fmt::Debug));
+        assert_quote_eq!(item.self_ty_path().unwrap(), quote!(a::A<T>));
+        assert!(item.find_fn("fmt").is_some());
+        assert!(item.find_fn("Output").is_none());
+        assert!(item.find_const("N").is_some());
+        assert!(item.find_type("Output").is_some());
+        let new: Ident = parse_quote!(new);
+        item.find_fn_mut(&new).unwrap().sig.ident = parse_quote!(create);
+        let names: Vec<_> = item
+            .fns()
+            .iter()
+            .map(|f| f.sig().ident.to_string())
+            .collect();
+        assert_eq!(names, ["fmt", "create"]);
+
+        let item: ItemImpl = parse_quote! {
+            impl !Send for A {}
+        };
+        assert!(item.is_negative());
+    }
+
+    #[test]
+    fn test_add_item() {
+        let mut item: ItemImpl = parse_quote! {
+            impl A {
+                const X: u8 = 0;
+            }
+        };
+        item.add_item(parse_quote! { type X = u8; }).unwrap();
+        item.add_item(parse_quote! { fn f() {} }).unwrap();
+        let err = item.add_item(parse_quote! { fn X() {} }).unwrap_err();
+        assert_eq!(err.to_string(), "duplicate definitions with name `X`");
+        assert!(item.add_item(parse_quote! { const f: u8 = 0; }).is_err());
+        assert_eq!(item.items.len(), 3);
+    }
+}
@@ -0,0 +1,251 @@
+use crate::item::ItemLike;
+use proc_macro2::Span;
+use syn::{spanned::Spanned, Error, ForeignItem, ImplItem, Item, Result, TraitItem};
+
+/// Kind of [syn::Item], [syn::ImplItem], [syn::TraitItem] or [syn::ForeignItem]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ItemKind {
+    Const,
+    Enum,
+    ExternCrate,
+    Fn,
+    ForeignMod,
+    Impl,
+    Macro,
+    Mod,
+    Static,
+    Struct,
+    Trait,
+    TraitAlias,
+    Type,
+    Union,
+    Use,
+    Verbatim,
+}
+
+impl ItemKind {
+    /// Returns the human-readable name of the kind, e.g. `extern crate`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Const => "constant",
+            Self::Enum => "enum",
+            Self::ExternCrate => "extern crate",
+            Self::Fn => "function",
+            Self::ForeignMod => "extern block",
+            Self::Impl => "implementation",
+            Self::Macro => "macro",
+            Self::Mod => "module",
+            Self::Static => "static",
+            Self::Struct => "struct",
+            Self::Trait => "trait",
+            Self::TraitAlias => "trait alias",
+            Self::Type => "type",
+            Self::Union => "union",
+            Self::Use => "use item",
+            Self::Verbatim => "verbatim item",
+        }
+    }
+
+    /// Returns the name with an indefinite article, e.g. `an enum`
+    pub fn name_with_article(self) -> String {
+        let article = match self {
+            Self::Enum | Self::ExternCrate | Self::ForeignMod | Self::Impl => "an",
+            _ => "a",
+        };
+        format!("{} {}", article, self.name())
+    }
+}
+
+/// Returns `a struct`, `a struct or an enum`, `a struct, an enum or a union`...
+fn describe_kinds(kinds: &[ItemKind]) -> String {
+    let names: Vec<_> = kinds.iter().map(|kind| kind.name_with_article()).collect();
+    match names.split_last() {
+        None => "nothing".to_owned(),
+        Some((last, [])) => last.clone(),
+        Some((last, init)) => format!("{} or {}", init.join(", "), last),
+    }
+}
+
+/// Classification of items and "expected X, found Y" diagnostics using `crate::ext::ItemLike`
+pub trait ItemKindExt: ItemLike {
+    /// Returns the kind of the item
+    fn kind(&self) -> ItemKind;
+
+    /// Returns the span of the leading keyword like `struct` or `fn`.
+    ///
+    /// Macro invocations are spanned at their paths; verbatim items at the whole item.
+    fn keyword_span(&self) -> Span;
+
+    /// Constructs an "expected X, found Y" error spanned at the keyword
+    fn kind_error(&self, expected: &[ItemKind]) -> Error {
+        Error::new(
+            self.keyword_span(),
+            format!(
+                "expected {}, found {}",
+                describe_kinds(expected),
+                self.kind().name_with_article()
+            ),
+        )
+    }
+
+    /// Returns `Ok` if the kind is one of `expected`; otherwise [ItemKindExt::kind_error]
+    fn expect_kind(&self, expected: &[ItemKind]) -> Result<ItemKind> {
+        let kind = self.kind();
+        if expected.contains(&kind) {
+            Ok(kind)
+        } else {
+            Err(self.kind_error(expected))
+        }
+    }
+}
+
+impl ItemKindExt for Item {
+    fn kind(&self) -> ItemKind {
+        match self {
+            Item::Const(_) => ItemKind::Const,
+            Item::Enum(_) => ItemKind::Enum,
+            Item::ExternCrate(_) => ItemKind::ExternCrate,
+            Item::Fn(_) => ItemKind::Fn,
+            Item::ForeignMod(_) => ItemKind::ForeignMod,
+            Item::Impl(_) => ItemKind::Impl,
+            Item::Macro(_) => ItemKind::Macro,
+            Item::Mod(_) => ItemKind::Mod,
+            Item::Static(_) => ItemKind::Static,
+            Item::Struct(_) => ItemKind::Struct,
+            Item::Trait(_) => ItemKind::Trait,
+            Item::TraitAlias(_) => ItemKind::TraitAlias,
+            Item::Type(_) => ItemKind::Type,
+            Item::Union(_) => ItemKind::Union,
+            Item::Use(_) => ItemKind::Use,
+            _ => ItemKind::Verbatim,
+        }
+    }
+
+    fn keyword_span(&self) -> Span {
+        match self {
+            Item::Const(item) => item.const_token.span,
+            Item::Enum(item) => item.enum_token.span,
+            Item::ExternCrate(item) => item.extern_token.span,
+            Item::Fn(item) => item.sig.fn_token.span,
+            Item::ForeignMod(item) => item.abi.extern_token.span,
+            Item::Impl(item) => item.impl_token.span,
+            Item::Macro(item) => item.mac.path.span(),
+            Item::Mod(item) => item.mod_token.span,
+            Item::Static(item) => item.static_token.span,
+            Item::Struct(item) => item.struct_token.span,
+            Item::Trait(item) => item.trait_token.span,
+            Item::TraitAlias(item) => item.trait_token.span,
+            Item::Type(item) => item.type_token.span,
+            Item::Union(item) => item.union_token.span,
+            Item::Use(item) => item.use_token.span,
+            other => other.span(),
+        }
+    }
+}
+
+impl ItemKindExt for ImplItem {
+    fn kind(&self) -> ItemKind {
+        match self {
+            ImplItem::Const(_) => ItemKind::Const,
+            ImplItem::Fn(_) => ItemKind::Fn,
+            ImplItem::Type(_) => ItemKind::Type,
+            ImplItem::Macro(_) => ItemKind::Macro,
+            _ => ItemKind::Verbatim,
+        }
+    }
+
+    fn keyword_span(&self) -> Span {
+        match self {
+            ImplItem::Const(item) => item.const_token.span,
+            ImplItem::Fn(item) => item.sig.fn_token.span,
+            ImplItem::Type(item) => item.type_token.span,
+            ImplItem::Macro(item) => item.mac.path.span(),
+            other => other.span(),
+        }
+    }
+}
+
+impl ItemKindExt for TraitItem {
+    fn kind(&self) -> ItemKind {
+        match self {
+            TraitItem::Const(_) => ItemKind::Const,
+            TraitItem::Fn(_) => ItemKind::Fn,
+            TraitItem::Type(_) => ItemKind::Type,
+            TraitItem::Macro(_) => ItemKind::Macro,
+            _ => ItemKind::Verbatim,
+        }
+    }
+
+    fn keyword_span(&self) -> Span {
+        match self {
+            TraitItem::Const(item) => item.const_token.span,
+            TraitItem::Fn(item) => item.sig.fn_token.span,
+            TraitItem::Type(item) => item.type_token.span,
+            TraitItem::Macro(item) => item.mac.path.span(),
+            other => other.span(),
+        }
+    }
+}
+
+impl ItemKindExt for ForeignItem {
+    fn kind(&self) -> ItemKind {
+        match self {
+            ForeignItem::Fn(_) => ItemKind::Fn,
+            ForeignItem::Static(_) => ItemKind::Static,
+            ForeignItem::Type(_) => ItemKind::Type,
+            ForeignItem::Macro(_) => ItemKind::Macro,
+            _ => ItemKind::Verbatim,
+        }
+    }
+
+    fn keyword_span(&self) -> Span {
+        match self {
+            ForeignItem::Fn(item) => item.sig.fn_token.span,
+            ForeignItem::Static(item) => item.static_token.span,
+            ForeignItem::Type(item) => item.type_token.span,
+            ForeignItem::Macro(item) => item.mac.path.span(),
+            other => other.span(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_expect_kind() {
+        let item: Item = parse_quote! {
+            pub trait A {}
+        };
+        assert_eq!(item.kind(), ItemKind::Trait);
+        assert_eq!(
+            item.expect_kind(&[ItemKind::Trait]).unwrap(),
+            ItemKind::Trait
+        );
+        let err = item
+            .expect_kind(&[ItemKind::Struct, ItemKind::Enum])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected a struct or an enum, found a trait"
+        );
+        let err = item.kind_error(&[ItemKind::Struct, ItemKind::Enum, ItemKind::Union]);
+        assert_eq!(
+            err.to_string(),
+            "expected a struct, an enum or a union, found a trait"
+        );
+
+        let item: ImplItem = parse_quote! {
+            type A = u8;
+        };
+        let err = item.expect_kind(&[ItemKind::Fn]).unwrap_err();
+        assert_eq!(err.to_string(), "expected a function, found a type");
+
+        let item: ForeignItem = parse_quote! {
+            static A: u8;
+        };
+        assert_eq!(item.kind(), ItemKind::Static);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::ident::GetIdent;
+use crate::item::{ConstLike, FunctionLike, ItemLike, TypeLike};
+use syn::{Ident, ItemTrait, Path, TypeParamBound};
+
+/// Extension for [syn::ItemTrait]
+pub trait ItemTraitExt {
+    /// Returns every method and associated function
+    fn fns(&self) -> Vec<&dyn FunctionLike>;
+    /// Returns methods and associated functions without default bodies
+    fn required_fns(&self) -> Vec<&dyn FunctionLike>;
+    /// Returns methods and associated functions with default bodies
+    fn provided_fns(&self) -> Vec<&dyn FunctionLike>;
+    /// Returns methods with `where Self: Sized`, which are excluded from trait objects
+    fn sized_escaped_fns(&self) -> Vec<&dyn FunctionLike>;
+    /// Returns the method or associated function named `name`
+    fn find_fn<N>(&self, name: &N) -> Option<&dyn FunctionLike>
+    where
+        N: ?Sized,
+        Ident: PartialEq<N>;
+
+    /// Returns associated types; `TypeLike::ty()` is the default
+    fn assoc_types(&self) -> Vec<&dyn TypeLike>;
+    /// Returns associated consts; `ConstLike::expr()` is the default
+    fn assoc_consts(&self) -> Vec<&dyn ConstLike>;
+
+    /// Returns paths of supertraits. Lifetime bounds are skipped.
+    fn supertrait_paths(&self) -> Vec<&Path>;
+    /// Returns idents of generic parameters including lifetimes
+    fn generic_idents(&self) -> Vec<&Ident>;
+}
+
+impl ItemTraitExt for ItemTrait {
+    fn fns(&self) -> Vec<&dyn FunctionLike> {
+        self.items
+            .iter()
+            .filter_map(|item| item.function_or_method().ok())
+            .collect()
+    }
+
+    fn required_fns(&self) -> Vec<&dyn FunctionLike> {
+        self.fns()
+            .into_iter()
+            .filter(|f| f.block().is_none())
+            .collect()
+    }
+
+    fn provided_fns(&self) -> Vec<&dyn FunctionLike> {
+        self.fns()
+            .into_iter()
+            .filter(|f| f.block().is_some())
+            .collect()
+    }
+
+    fn sized_escaped_fns(&self) -> Vec<&dyn FunctionLike> {
+        self.fns()
+            .into_iter()
+            .filter(|f| f.has_sized_escape())
+            .collect()
+    }
+
+    fn find_fn<N>(&self, name: &N) -> Option<&dyn FunctionLike>
+    where
+        N: ?Sized,
+        Ident: PartialEq<N>,
+    {
+        self.fns().into_iter().find(|f| f.sig().ident == *name)
+    }
+
+    fn assoc_types(&self) -> Vec<&dyn TypeLike> {
+        self.items
+            .iter()
+            .filter_map(|item| item.type_alias().ok())
+            .collect()
+    }
+
+    fn assoc_consts(&self) -> Vec<&dyn ConstLike> {
+        self.items
+            .iter()
+            .filter_map(|item| item.constant().ok())
+            .collect()
+    }
+
+    fn supertrait_paths(&self) -> Vec<&Path> {
+        self.supertraits
+            .iter()
+            .filter_map(|bound| match bound {
+                TypeParamBound::Trait(bound) => Some(&bound.path),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn generic_idents(&self) -> Vec<&Ident> {
+        self.generics
+            .params
+            .iter()
+            .filter_map(|param| param.get_ident())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_trait_analysis() {
+        let item: ItemTrait = parse_quote! {
+            pub trait A<'a, T>: Clone + fmt::Debug + 'a {
+                type Output;
+                type Error = ();
+                const N: usize;
+                const M: usize = 0;
+                fn required(&self) -> Self::Output;
+                fn provided(&self) {}
+                fn sized(self) where Self: Sized;
+                fn maybe(&self) where Self: ?Sized;
+            }
+        };
+        let names = |fns: Vec<&dyn FunctionLike>| -> Vec<String> {
+            fns.iter().map(|f| f.sig().ident.to_string()).collect()
+        };
+        assert_eq!(names(item.required_fns()), ["required", "sized", "maybe"]);
+        assert_eq!(names(item.provided_fns()), ["provided"]);
+        assert_eq!(names(item.sized_escaped_fns()), ["sized"]);
+        assert!(item.find_fn("provided").unwrap().block().is_some());
+
+        let types: Vec<_> = item
+            .assoc_types()
+            .iter()
+            .map(|t| (t.ident().to_string(), t.ty().is_some()))
+            .collect();
+        assert_eq!(
+            types,
+            [("Output".to_owned(), false), ("Error".to_owned(), true)]
+        );
+        let consts: Vec<_> = item
+            .assoc_consts()
+            .iter()
+            .map(|c| (c.ident().to_string(), c.expr().is_some()))
+            .collect();
+        assert_eq!(consts, [("N".to_owned(), false), ("M".to_owned(), true)]);
+
+        let supertraits: Vec<_> = item
+            .supertrait_paths()
+            .iter()
+            .map(|path| quote::quote!(#path).to_string())
+            .collect();
+        assert_eq!(supertraits, ["Clone", "fmt :: Debug"]);
+        assert_eq!(item.generic_idents(), ["a", "T"]);
+    }
+}
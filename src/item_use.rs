@@ -0,0 +1,414 @@
+use crate::ident::GetIdent;
+use proc_macro2::Span;
+use syn::{
+    punctuated::Punctuated, Error, Ident, ItemUse, Path, PathSegment, Result, Token, UseGlob,
+    UseGroup, UseName, UsePath, UseRename, UseTree, Visibility,
+};
+
+/// A single flattened path of [syn::UseTree]
+///
+/// `use a::{b, c::{d as e, *}};` is flattened into `a::b`, `a::c::d as e` and `a::c::*`.
+#[derive(Clone)]
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+pub struct UseEntry {
+    pub leading_colon: Option<Token![::]>,
+    /// Full path segments. `self` in a group is resolved to its parent path.
+    pub segments: Vec<Ident>,
+    pub rename: Option<Ident>,
+    pub glob: bool,
+}
+
+impl UseEntry {
+    /// Constructs and returns a [syn::Path] of the segments; glob is not included.
+    pub fn path(&self) -> Path {
+        Path {
+            leading_colon: self.leading_colon,
+            segments: self
+                .segments
+                .iter()
+                .cloned()
+                .map(PathSegment::from)
+                .collect(),
+        }
+    }
+
+    fn same_as(&self, other: &Self) -> bool {
+        self.leading_colon.is_some() == other.leading_colon.is_some()
+            && self.segments == other.segments
+            && self.rename == other.rename
+            && self.glob == other.glob
+    }
+}
+
+impl GetIdent for UseEntry {
+    /// Returns the name bound by this entry; `None` for glob
+    fn get_ident(&self) -> Option<&Ident> {
+        if self.glob {
+            None
+        } else {
+            self.rename.as_ref().or_else(|| self.segments.last())
+        }
+    }
+}
+
+/// Extension for [syn::ItemUse]
+pub trait ItemUseExt {
+    /// Returns every full path of the use tree with bound names and glob flags
+    fn flatten(&self) -> Vec<UseEntry>;
+
+    /// Constructs and returns a new [syn::ItemUse] grouping common prefixes of `entries`.
+    ///
+    /// Duplicated entries are merged. `Err` if `entries` is empty, an entry without segments
+    /// is not a glob, or the entries disagree on the leading colon.
+    fn from_entries<I>(entries: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = UseEntry>,
+        Self: Sized;
+
+    /// Rebuilds the tree as sorted, deduplicated and minimally grouped
+    fn normalize(&mut self);
+
+    /// Merges paths of `other` into the tree of self. attrs and vis of self are kept.
+    ///
+    /// `Err` if the leading colons are different.
+    fn merge(&mut self, other: &ItemUse) -> Result<()>;
+}
+
+impl ItemUseExt for ItemUse {
+    fn flatten(&self) -> Vec<UseEntry> {
+        let mut entries = Vec::new();
+        flatten_tree(
+            &self.tree,
+            &mut Vec::new(),
+            self.leading_colon,
+            &mut entries,
+        );
+        entries
+    }
+
+    fn from_entries<I>(entries: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = UseEntry>,
+    {
+        let mut entries = entries.into_iter();
+        let first = entries
+            .next()
+            .ok_or_else(|| Error::new(Span::call_site(), "no use entry is given"))?;
+        let leading_colon = first.leading_colon;
+        let mut root = UseNode::default();
+        for entry in std::iter::once(first).chain(entries) {
+            if entry.leading_colon.is_some() != leading_colon.is_some() {
+                return Err(Error::new(
+                    entry
+                        .segments
+                        .first()
+                        .map_or_else(Span::call_site, Ident::span),
+                    "use entries have different leading colons",
+                ));
+            }
+            if entry.segments.is_empty() && !entry.glob {
+                return Err(Error::new(
+                    entry
+                        .rename
+                        .as_ref()
+                        .map_or_else(Span::call_site, Ident::span),
+                    "use entry must have at least one segment",
+                ));
+            }
+            root.insert(&entry.segments, entry.rename, entry.glob);
+        }
+        let mut trees = root.subtrees();
+        let tree = if trees.len() == 1 {
+            trees.pop().unwrap()
+        } else {
+            UseTree::Group(UseGroup {
+                brace_token: Default::default(),
+                items: trees.into_iter().collect(),
+            })
+        };
+        Ok(ItemUse {
+            attrs: Vec::new(),
+            vis: Visibility::Inherited,
+            use_token: Default::default(),
+            leading_colon,
+            tree,
+            semi_token: Default::default(),
+        })
+    }
+
+    fn normalize(&mut self) {
+        let mut entries = self.flatten();
+        entries.sort_by_cached_key(sort_key);
+        self.tree = if entries.is_empty() {
+            empty_tree()
+        } else {
+            // entries of a single item share the leading colon and have segments or glob
+            Self::from_entries(entries)
+                .expect("flattened entries are always valid")
+                .tree
+        };
+    }
+
+    fn merge(&mut self, other: &ItemUse) -> Result<()> {
+        if self.leading_colon.is_some() != other.leading_colon.is_some() {
+            return Err(Error::new_spanned(
+                other,
+                "use items with different leading colons can't be merged",
+            ));
+        }
+        let entries: Vec<_> = self.flatten().into_iter().chain(other.flatten()).collect();
+        self.tree = if entries.is_empty() {
+            empty_tree()
+        } else {
+            Self::from_entries(entries)?.tree
+        };
+        Ok(())
+    }
+}
+
+/// `{}`, the tree importing nothing
+fn empty_tree() -> UseTree {
+    UseTree::Group(UseGroup {
+        brace_token: Default::default(),
+        items: Punctuated::new(),
+    })
+}
+
+fn sort_key(entry: &UseEntry) -> (Vec<String>, bool, Option<String>) {
+    (
+        entry.segments.iter().map(Ident::to_string).collect(),
+        entry.glob,
+        entry.rename.as_ref().map(Ident::to_string),
+    )
+}
+
+fn flatten_tree(
+    tree: &UseTree,
+    prefix: &mut Vec<Ident>,
+    leading_colon: Option<Token![::]>,
+    entries: &mut Vec<UseEntry>,
+) {
+    let mut push = |segments: Vec<Ident>, rename: Option<Ident>, glob: bool| {
+        let entry = UseEntry {
+            leading_colon,
+            segments,
+            rename,
+            glob,
+        };
+        if !entries.iter().any(|e| e.same_as(&entry)) {
+            entries.push(entry);
+        }
+    };
+    let leaf = |prefix: &Vec<Ident>, ident: &Ident| {
+        let mut segments = prefix.clone();
+        if ident != "self" || prefix.is_empty() {
+            segments.push(ident.clone());
+        }
+        segments
+    };
+    match tree {
+        UseTree::Path(path) => {
+            prefix.push(path.ident.clone());
+            flatten_tree(&path.tree, prefix, leading_colon, entries);
+            prefix.pop();
+        }
+        UseTree::Name(name) => push(leaf(prefix, &name.ident), None, false),
+        UseTree::Rename(rename) => push(
+            leaf(prefix, &rename.ident),
+            Some(rename.rename.clone()),
+            false,
+        ),
+        UseTree::Glob(_) => push(prefix.clone(), None, true),
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                flatten_tree(tree, prefix, leading_colon, entries);
+            }
+        }
+    }
+}
+
+/// Prefix tree of use entries
+#[derive(Default)]
+struct UseNode {
+    /// Entries ending at this node; `Some` if renamed
+    names: Vec<Option<Ident>>,
+    glob: bool,
+    children: Vec<(Ident, UseNode)>,
+}
+
+impl UseNode {
+    fn insert(&mut self, segments: &[Ident], rename: Option<Ident>, glob: bool) {
+        match segments.split_first() {
+            None if glob => self.glob = true,
+            None => {
+                if !self.names.contains(&rename) {
+                    self.names.push(rename)
+                }
+            }
+            Some((first, rest)) => {
+                let index = match self.children.iter().position(|(i, _)| i == first) {
+                    Some(index) => index,
+                    None => {
+                        self.children.push((first.clone(), UseNode::default()));
+                        self.children.len() - 1
+                    }
+                };
+                self.children[index].1.insert(rest, rename, glob)
+            }
+        }
+    }
+
+    /// Returns trees of children and glob, without names ending at this node
+    fn subtrees(&self) -> Vec<UseTree> {
+        let mut trees: Vec<_> = self
+            .children
+            .iter()
+            .map(|(ident, node)| node.tree(ident))
+            .collect();
+        if self.glob {
+            trees.push(UseTree::Glob(UseGlob {
+                star_token: Default::default(),
+            }));
+        }
+        trees
+    }
+
+    fn tree(&self, ident: &Ident) -> UseTree {
+        let subtrees = self.subtrees();
+        if subtrees.is_empty() && self.names.len() == 1 {
+            return leaf_tree(ident.clone(), self.names[0].clone());
+        }
+        let self_ident = Ident::new("self", ident.span());
+        let mut trees: Vec<_> = self
+            .names
+            .iter()
+            .map(|rename| leaf_tree(self_ident.clone(), rename.clone()))
+            .chain(subtrees)
+            .collect();
+        let tree = if trees.len() == 1 {
+            trees.pop().unwrap()
+        } else {
+            UseTree::Group(UseGroup {
+                brace_token: Default::default(),
+                items: trees.into_iter().collect::<Punctuated<_, _>>(),
+            })
+        };
+        UseTree::Path(UsePath {
+            ident: ident.clone(),
+            colon2_token: Default::default(),
+            tree: Box::new(tree),
+        })
+    }
+}
+
+fn leaf_tree(ident: Ident, rename: Option<Ident>) -> UseTree {
+    match rename {
+        Some(rename) => UseTree::Rename(UseRename {
+            ident,
+            as_token: Default::default(),
+            rename,
+        }),
+        None => UseTree::Name(UseName { ident }),
+    }
+}
+
+/// Returns the ident bound by a single-path use tree
+pub(crate) fn use_tree_ident(tree: &UseTree) -> Option<&Ident> {
+    match tree {
+        UseTree::Path(UsePath { tree, .. }) => use_tree_ident(tree),
+        UseTree::Name(UseName { ident }) if ident != "self" => Some(ident),
+        UseTree::Rename(UseRename { rename, .. }) => Some(rename),
+        UseTree::Group(UseGroup { items, .. }) if items.len() == 1 => {
+            use_tree_ident(items.first().unwrap())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_quote_eq;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_flatten() {
+        let item: ItemUse = parse_quote! { use a::{b, c::{self as f, d as e, *}}; };
+        let entries = item.flatten();
+        let paths: Vec<_> = entries
+            .iter()
+            .map(|e| {
+                let path = e.path();
+                quote::quote!(#path).to_string()
+            })
+            .collect();
+        assert_eq!(paths, vec!["a :: b", "a :: c", "a :: c :: d", "a :: c"]);
+        let names: Vec<_> = entries
+            .iter()
+            .map(|e| e.get_ident().map(|i| i.to_string()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                Some("b".to_owned()),
+                Some("f".to_owned()),
+                Some("e".to_owned()),
+                None
+            ]
+        );
+        assert!(entries[3].glob);
+    }
+
+    #[test]
+    fn test_from_entries_round_trip() {
+        let item: ItemUse = parse_quote! { use ::a::{b, c::{self, d as e, *}}; };
+        let rebuilt = ItemUse::from_entries(item.flatten()).unwrap();
+        assert_quote_eq!(rebuilt, item);
+
+        let item: ItemUse = parse_quote! { use a::b::c; };
+        let rebuilt = ItemUse::from_entries(item.flatten()).unwrap();
+        assert_quote_eq!(rebuilt, item);
+
+        assert!(ItemUse::from_entries(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut item: ItemUse = parse_quote! { pub use a::{c, b::{x}, c, b::y, self}; };
+        item.normalize();
+        let expected: ItemUse = parse_quote! { pub use a::{self, b::{x, y}, c}; };
+        assert_quote_eq!(item, expected);
+
+        let mut item: ItemUse = parse_quote! { use a::{b::{}}; };
+        item.normalize();
+        let expected: ItemUse = parse_quote! { use {}; };
+        assert_quote_eq!(item, expected);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut item: ItemUse = parse_quote! { use a::b; };
+        item.merge(&parse_quote! { use a::c::d; }).unwrap();
+        item.merge(&parse_quote! { use e; }).unwrap();
+        let expected: ItemUse = parse_quote! { use {a::{b, c::d}, e}; };
+        assert_quote_eq!(item, expected);
+
+        assert!(item.merge(&parse_quote! { use ::f; }).is_err());
+
+        let mut item: ItemUse = parse_quote! { use a::{}; };
+        item.merge(&parse_quote! { use b::{}; }).unwrap();
+        assert_quote_eq!(item, quote! { use {}; });
+        item.merge(&parse_quote! { use c; }).unwrap();
+        assert_quote_eq!(item, quote! { use c; });
+    }
+
+    #[test]
+    fn test_use_tree_ident() {
+        let item: ItemUse = parse_quote! { use a::b as c; };
+        assert_eq!(use_tree_ident(&item.tree).unwrap(), "c");
+        let item: ItemUse = parse_quote! { use a::{b}; };
+        assert_eq!(use_tree_ident(&item.tree).unwrap(), "b");
+        let item: ItemUse = parse_quote! { use a::{b, c}; };
+        assert!(use_tree_ident(&item.tree).is_none());
+    }
+}
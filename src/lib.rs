@@ -5,18 +5,60 @@
 
 #[cfg(any(feature = "derive", feature = "full"))]
 mod attribute;
+#[cfg(all(feature = "full", feature = "parsing", feature = "printing"))]
+mod blanket;
+#[cfg(all(any(feature = "derive", feature = "full"), feature = "parsing"))]
+mod builtin;
+#[cfg(all(feature = "full", feature = "parsing", feature = "printing"))]
+mod delegate;
+#[cfg(all(any(feature = "derive", feature = "full"), feature = "parsing"))]
+mod derives;
+#[cfg(all(any(feature = "derive", feature = "full"), feature = "printing"))]
+mod destructure;
+#[cfg(any(
+    all(any(feature = "derive", feature = "full"), feature = "parsing"),
+    all(feature = "full", feature = "printing")
+))]
+mod error;
+#[cfg(all(any(feature = "derive", feature = "full"), feature = "printing"))]
+mod fields;
 #[cfg(any(feature = "derive", feature = "full"))]
 mod generics;
 mod ident;
 #[cfg(feature = "full")]
 mod item;
+#[cfg(feature = "full")]
+mod item_impl;
+#[cfg(feature = "full")]
+mod item_kind;
+#[cfg(feature = "full")]
+mod item_trait;
+#[cfg(feature = "full")]
+mod item_use;
+#[cfg(all(any(feature = "derive", feature = "full"), feature = "parsing"))]
+mod lints;
 #[cfg(any(feature = "derive", feature = "full"))]
 mod meta;
+#[cfg(all(feature = "full", feature = "parsing", feature = "printing"))]
+mod mock;
+#[cfg(all(feature = "full", feature = "parsing"))]
+mod mod_loader;
+#[cfg(all(feature = "full", feature = "printing"))]
+mod object_safety;
 mod path;
+#[cfg(all(any(feature = "derive", feature = "full"), feature = "parsing"))]
+mod placement;
 mod punctuated;
+#[cfg(all(any(feature = "derive", feature = "full"), feature = "parsing"))]
+mod repr;
+#[cfg(all(feature = "full", feature = "parsing", feature = "printing"))]
+mod skeleton;
 #[cfg(test)]
-#[macro_use]
 mod test;
+#[cfg(all(feature = "full", feature = "parsing"))]
+mod testing;
+#[cfg(all(feature = "full", feature = "parsing", feature = "printing"))]
+mod verbatim;
 
 /// `use syn_ext::ext::*`;  // Namespace module for extension traits.
 ///
@@ -33,15 +75,40 @@ pub mod ext {
         #[cfg(feature = "parsing")]
         pub use crate::attribute::{AttributeExt, AttributeIteratorExt};
         #[cfg(feature = "parsing")]
+        pub use crate::derives::DeriveAttrsExt;
+        #[cfg(feature = "printing")]
+        pub use crate::fields::FieldsLike;
+        #[cfg(feature = "parsing")]
+        pub use crate::lints::LintAttrsExt;
+        #[cfg(feature = "parsing")]
         pub use crate::meta::MetaAttributeExt;
         pub use crate::meta::{
             MetaExt, MetaIteratorExt, NestedMetaIteratorExt, NestedMetaRefIteratorExt,
         };
         pub use crate::path::GetPath;
+        #[cfg(feature = "parsing")]
+        pub use crate::repr::ReprAttrsExt;
     }
     #[cfg(feature = "full")]
     mod full {
-        pub use crate::item::{ItemAttrExt, ItemLike, ItemModExt};
+        #[cfg(feature = "parsing")]
+        pub use crate::builtin::ItemBuiltinAttrExt;
+        pub use crate::item::{
+            ConstLike, FunctionLike, ItemAttrExt, ItemLike, ItemModExt, MacroLike, StaticLike,
+            TypeLike,
+        };
+        pub use crate::item_impl::ItemImplExt;
+        pub use crate::item_kind::ItemKindExt;
+        pub use crate::item_trait::ItemTraitExt;
+        pub use crate::item_use::ItemUseExt;
+        #[cfg(feature = "parsing")]
+        pub use crate::lints::ItemLintExt;
+        #[cfg(feature = "printing")]
+        pub use crate::object_safety::ObjectSafetyExt;
+        #[cfg(feature = "parsing")]
+        pub use crate::testing::{TestFnExt, TestModExt};
+        #[cfg(all(feature = "parsing", feature = "printing"))]
+        pub use crate::verbatim::VerbatimExt;
     }
 
     pub use basic::*;
@@ -52,6 +119,38 @@ pub mod ext {
 }
 
 pub mod types {
+    #[cfg(all(feature = "full", feature = "parsing", feature = "printing"))]
+    pub use crate::blanket::BlanketPtr;
+    #[cfg(all(any(feature = "derive", feature = "full"), feature = "parsing"))]
+    pub use crate::builtin::{BuiltinAttr, Deprecated, Inline};
+    #[cfg(all(feature = "full", feature = "parsing", feature = "printing"))]
+    pub use crate::delegate::DelegateImpl;
+    #[cfg(all(any(feature = "derive", feature = "full"), feature = "parsing"))]
+    pub use crate::derives::BuiltinDerive;
+    #[cfg(all(any(feature = "derive", feature = "full"), feature = "printing"))]
+    pub use crate::destructure::{Binding, BindingMode, Destructure};
+    #[cfg(all(any(feature = "derive", feature = "full"), feature = "printing"))]
+    pub use crate::fields::FieldsShape;
+    #[cfg(feature = "full")]
+    pub use crate::item_kind::ItemKind;
+    #[cfg(feature = "full")]
+    pub use crate::item_use::UseEntry;
+    #[cfg(all(any(feature = "derive", feature = "full"), feature = "parsing"))]
+    pub use crate::lints::LintLevel;
     #[cfg(any(feature = "derive", feature = "full"))]
     pub use crate::meta::{Meta1 as Meta, MetaList1 as MetaList, NestedMeta, PunctuatedNestedMeta};
+    #[cfg(all(feature = "full", feature = "parsing", feature = "printing"))]
+    pub use crate::mock::MockImpl;
+    #[cfg(all(feature = "full", feature = "parsing"))]
+    pub use crate::mod_loader::ModuleTree;
+    #[cfg(all(any(feature = "derive", feature = "full"), feature = "parsing"))]
+    pub use crate::placement::{AttrPlacement, AttrTarget};
+    #[cfg(all(any(feature = "derive", feature = "full"), feature = "parsing"))]
+    pub use crate::repr::{Repr, ReprHint, ReprInt};
+    #[cfg(all(feature = "full", feature = "parsing", feature = "printing"))]
+    pub use crate::skeleton::ImplSkeleton;
+    #[cfg(all(feature = "full", feature = "parsing"))]
+    pub use crate::testing::{ShouldPanic, TestAttrs};
+    #[cfg(all(feature = "full", feature = "parsing", feature = "printing"))]
+    pub use crate::verbatim::VerbatimItem;
 }
@@ -0,0 +1,349 @@
+use crate::attribute::AttributeExt;
+#[cfg(feature = "full")]
+use crate::item::ItemLike;
+use crate::meta::{Meta1, NestedMeta};
+use proc_macro2::Span;
+use syn::{parse_quote, Attribute, Error, Ident, Path, Result};
+
+/// Level of a lint attribute
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+    Forbid,
+    Expect,
+}
+
+impl LintLevel {
+    pub const ALL: [Self; 5] = [
+        Self::Allow,
+        Self::Warn,
+        Self::Deny,
+        Self::Forbid,
+        Self::Expect,
+    ];
+
+    /// Returns the name of the attribute
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Warn => "warn",
+            Self::Deny => "deny",
+            Self::Forbid => "forbid",
+            Self::Expect => "expect",
+        }
+    }
+
+    /// Returns the level of a lint attribute path
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ident = path.get_ident()?;
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|level| ident == level.name())
+    }
+
+    /// Constructs and returns the path of the attribute
+    pub fn to_path(self) -> Path {
+        Ident::new(self.name(), Span::call_site()).into()
+    }
+}
+
+/// Returns a key to compare lint paths, e.g. `clippy::foo`
+fn lint_key(path: &Path) -> String {
+    let segments: Vec<_> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    segments.join("::")
+}
+
+/// Returns lint paths of a lint attribute. `reason = "..."` is skipped.
+fn lint_paths(attr: &Attribute) -> Result<Vec<Path>> {
+    let list = attr.promoted_list()?;
+    let mut paths = Vec::new();
+    for nested in &list.nested {
+        match nested {
+            NestedMeta::Meta(Meta1::Path(path)) => paths.push(path.clone()),
+            NestedMeta::Meta(Meta1::NameValue(nv)) if nv.path.is_ident("reason") => {}
+            other => return Err(Error::new_spanned(other, "expected lint path")),
+        }
+    }
+    Ok(paths)
+}
+
+fn lints_of(attrs: &[Attribute]) -> Result<Vec<(LintLevel, Path)>> {
+    let mut lints = Vec::new();
+    for attr in attrs {
+        if let Some(level) = LintLevel::from_path(attr.path()) {
+            lints.extend(lint_paths(attr)?.into_iter().map(|path| (level, path)));
+        }
+    }
+    Ok(lints)
+}
+
+fn lint_level_of(attrs: &[Attribute], lint: &Path) -> Result<Option<LintLevel>> {
+    let key = lint_key(lint);
+    Ok(lints_of(attrs)?
+        .into_iter()
+        .filter(|(_, path)| lint_key(path) == key)
+        .map(|(level, _)| level)
+        .next_back())
+}
+
+fn allowed_lints_of(attrs: &[Attribute]) -> Result<Vec<Path>> {
+    let lints = lints_of(attrs)?;
+    let mut allowed: Vec<Path> = Vec::new();
+    for (_, path) in lints.iter().filter(|(level, _)| *level == LintLevel::Allow) {
+        let key = lint_key(path);
+        let last = lints
+            .iter()
+            .filter(|(_, p)| lint_key(p) == key)
+            .map(|(level, _)| *level)
+            .next_back();
+        if last == Some(LintLevel::Allow) && allowed.iter().all(|p| lint_key(p) != key) {
+            allowed.push(path.clone());
+        }
+    }
+    Ok(allowed)
+}
+
+fn is_doc_hidden(attr: &Attribute) -> bool {
+    attr.path().is_ident("doc")
+        && attr.promoted_list().is_ok_and(|list| {
+            list.nested.iter().any(
+                |nested| matches!(nested, NestedMeta::Meta(Meta1::Path(p)) if p.is_ident("hidden")),
+            )
+        })
+}
+
+/// Extension for lint attributes of `Vec<[syn::Attribute]>`
+///
+/// Lint attributes are `#[allow(...)]`, `#[warn(...)]`, `#[deny(...)]`, `#[forbid(...)]`
+/// and `#[expect(...)]`. Tool lints like `clippy::foo` are compared by their full paths.
+pub trait LintAttrsExt {
+    /// Returns every lint with its level in order of attributes
+    fn lints(&self) -> Result<Vec<(LintLevel, Path)>>;
+
+    /// Returns the effective level of `lint`; the last attribute wins
+    fn lint_level(&self, lint: &Path) -> Result<Option<LintLevel>>;
+
+    /// Returns lints whose effective level is `allow`
+    fn allowed_lints(&self) -> Result<Vec<Path>>;
+
+    /// Adds `lint` to the first attribute of `level` unless already listed there.
+    ///
+    /// A new attribute is pushed if there is no attribute of `level`.
+    /// Returns `true` if added.
+    fn add_lint(&mut self, level: LintLevel, lint: Path) -> Result<bool>;
+
+    /// Merges `lints` into the first `#[allow(...)]`. See [LintAttrsExt::add_lint]
+    fn allow<I>(&mut self, lints: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Path>;
+
+    /// Returns `true` if `#[automatically_derived]` exists
+    fn is_automatically_derived(&self) -> bool;
+
+    /// Pushes `#[automatically_derived]` unless exists. Returns `true` if added.
+    fn add_automatically_derived(&mut self) -> bool;
+
+    /// Returns `true` if `#[doc(hidden)]` exists
+    fn is_doc_hidden(&self) -> bool;
+
+    /// Pushes `#[doc(hidden)]` unless exists. Returns `true` if added.
+    fn add_doc_hidden(&mut self) -> bool;
+}
+
+impl LintAttrsExt for Vec<Attribute> {
+    fn lints(&self) -> Result<Vec<(LintLevel, Path)>> {
+        lints_of(self)
+    }
+
+    fn lint_level(&self, lint: &Path) -> Result<Option<LintLevel>> {
+        lint_level_of(self, lint)
+    }
+
+    fn allowed_lints(&self) -> Result<Vec<Path>> {
+        allowed_lints_of(self)
+    }
+
+    fn add_lint(&mut self, level: LintLevel, lint: Path) -> Result<bool> {
+        let key = lint_key(&lint);
+        let attr = match self
+            .iter_mut()
+            .find(|attr| LintLevel::from_path(attr.path()) == Some(level))
+        {
+            Some(attr) => attr,
+            None => {
+                let path = level.to_path();
+                self.push(parse_quote!(#[#path(#lint)]));
+                return Ok(true);
+            }
+        };
+        if lint_paths(attr)?.iter().any(|p| lint_key(p) == key) {
+            return Ok(false);
+        }
+        attr.try_promoted_list_mut(Default::default(), |list| {
+            // `reason` must come last
+            let index = list
+                .nested
+                .iter()
+                .position(|nested| !matches!(nested, NestedMeta::Meta(Meta1::Path(_))))
+                .unwrap_or(list.nested.len());
+            list.nested
+                .insert(index, NestedMeta::Meta(Meta1::Path(lint)));
+            Ok(())
+        })?;
+        Ok(true)
+    }
+
+    fn allow<I>(&mut self, lints: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Path>,
+    {
+        for lint in lints {
+            self.add_lint(LintLevel::Allow, lint)?;
+        }
+        Ok(())
+    }
+
+    fn is_automatically_derived(&self) -> bool {
+        self.iter()
+            .any(|attr| attr.path().is_ident("automatically_derived"))
+    }
+
+    fn add_automatically_derived(&mut self) -> bool {
+        if self.is_automatically_derived() {
+            return false;
+        }
+        self.push(parse_quote!(#[automatically_derived]));
+        true
+    }
+
+    fn is_doc_hidden(&self) -> bool {
+        self.iter().any(is_doc_hidden)
+    }
+
+    fn add_doc_hidden(&mut self) -> bool {
+        if LintAttrsExt::is_doc_hidden(self) {
+            return false;
+        }
+        self.push(parse_quote!(#[doc(hidden)]));
+        true
+    }
+}
+
+/// Lint levels of `syn::*Item` using `crate::ext::ItemLike`
+#[cfg(feature = "full")]
+pub trait ItemLintExt: ItemLike {
+    /// Returns the effective level of `lint` on the item. See [LintAttrsExt::lint_level]
+    fn lint_level(&self, lint: &Path) -> Result<Option<LintLevel>> {
+        lint_level_of(self.attrs()?, lint)
+    }
+
+    /// Returns lints allowed on the item. See [LintAttrsExt::allowed_lints]
+    fn allowed_lints(&self) -> Result<Vec<Path>> {
+        allowed_lints_of(self.attrs()?)
+    }
+}
+
+#[cfg(feature = "full")]
+impl ItemLintExt for syn::Item {}
+#[cfg(feature = "full")]
+impl ItemLintExt for syn::ImplItem {}
+#[cfg(feature = "full")]
+impl ItemLintExt for syn::TraitItem {}
+#[cfg(feature = "full")]
+impl ItemLintExt for syn::ForeignItem {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_quote_eq;
+    use crate::test::attrs;
+
+    fn keys(paths: &[Path]) -> Vec<String> {
+        paths.iter().map(lint_key).collect()
+    }
+
+    #[test]
+    fn test_lint_levels() {
+        let attrs = attrs(parse_quote! {
+            #[allow(dead_code, clippy::too_many_arguments, reason = "generated")]
+            #[warn(unused)]
+            #[deny(dead_code)]
+            struct A;
+        });
+        assert_eq!(attrs.lints().unwrap().len(), 4);
+        assert_eq!(
+            attrs.lint_level(&parse_quote!(dead_code)).unwrap(),
+            Some(LintLevel::Deny)
+        );
+        assert_eq!(attrs.lint_level(&parse_quote!(missing_docs)).unwrap(), None);
+        assert_eq!(
+            keys(&attrs.allowed_lints().unwrap()),
+            ["clippy::too_many_arguments"]
+        );
+    }
+
+    #[test]
+    fn test_add_lints() {
+        let mut attrs = attrs(parse_quote! {
+            #[doc = "a"]
+            #[allow(dead_code, reason = "generated")]
+            struct A;
+        });
+        attrs
+            .allow(vec![
+                parse_quote!(dead_code),
+                parse_quote!(clippy::all),
+                parse_quote!(unused),
+            ])
+            .unwrap();
+        assert!(attrs
+            .add_lint(LintLevel::Deny, parse_quote!(missing_docs))
+            .unwrap());
+        assert!(attrs.add_automatically_derived());
+        assert!(!attrs.add_automatically_derived());
+        assert!(attrs.add_doc_hidden());
+        assert!(!attrs.add_doc_hidden());
+        let expected = quote::quote! {
+            #[doc = "a"]
+            #[allow(dead_code, clippy::all, unused, reason = "generated")]
+            #[deny(missing_docs)]
+            #[automatically_derived]
+            #[doc(hidden)]
+        };
+        assert_quote_eq!(quote::quote!(#(#attrs)*), expected);
+    }
+
+    #[cfg(feature = "full")]
+    #[test]
+    fn test_item_lints() {
+        let item: syn::Item = parse_quote! {
+            #[allow(unused_variables)]
+            fn f() {}
+        };
+        assert_eq!(keys(&item.allowed_lints().unwrap()), ["unused_variables"]);
+        assert_eq!(
+            ItemLintExt::lint_level(&item, &parse_quote!(unused_variables)).unwrap(),
+            Some(LintLevel::Allow)
+        );
+
+        let mut block: syn::ItemForeignMod = parse_quote! {
+            extern "C" {
+                #[allow(non_snake_case)]
+                fn F();
+            }
+        };
+        let item = &mut block.items[0];
+        assert_eq!(keys(&item.allowed_lints().unwrap()), ["non_snake_case"]);
+        item.attrs_mut()
+            .unwrap()
+            .add_lint(LintLevel::Deny, parse_quote!(improper_ctypes))
+            .unwrap();
+        assert_eq!(
+            ItemLintExt::lint_level(item, &parse_quote!(improper_ctypes)).unwrap(),
+            Some(LintLevel::Deny)
+        );
+    }
+}
@@ -149,6 +149,7 @@ impl quote::ToTokens for MetaList1 {
 }
 
 #[derive(Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum NestedMeta {
     Meta(Meta1),
     Lit(Lit),
@@ -0,0 +1,361 @@
+use crate::error::combine;
+use crate::item::FunctionLike;
+use crate::item_trait::ItemTraitExt;
+use crate::skeleton::{arg_ident, named_sig, ImplSkeleton};
+use proc_macro2::{Group, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse_quote, Error, Expr, FnArg, GenericParam, Ident, Item, ItemImpl, ItemStruct, ItemTrait,
+    Result, ReturnType, Type,
+};
+
+/// Returns `true` if `tokens` contain `&` or a lifetime
+fn borrows(tokens: TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Punct(punct) => punct.as_char() == '&' || punct.as_char() == '\'',
+        TokenTree::Group(group) => borrows(group.stream()),
+        _ => false,
+    })
+}
+
+/// Generator of mock structs implementing a trait, for tests
+///
+/// For each required method `f`, the mock has `f_calls` recording the arguments of every call
+/// and `f_fn` computing the return value, set by `on_f`. Reference arguments are recorded as
+/// their `ToOwned::Owned` type and other arguments are cloned. Provided methods keep their
+/// default bodies.
+pub struct MockImpl {
+    ident: Ident,
+    types: Vec<(Ident, Type)>,
+    consts: Vec<(Ident, Expr)>,
+}
+
+/// Signature data of a mocked method
+struct MockedFn {
+    ident: Ident,
+    args: Vec<Ident>,
+    arg_tys: Vec<TokenStream>,
+    recorded_tys: Vec<TokenStream>,
+    recorded: Vec<TokenStream>,
+    output: TokenStream,
+}
+
+impl MockImpl {
+    /// Mocks with a struct named `ident`, e.g. `MockStore`
+    pub fn new(ident: Ident) -> Self {
+        Self {
+            ident,
+            types: Vec::new(),
+            consts: Vec::new(),
+        }
+    }
+
+    /// Sets the associated type `name`
+    pub fn assoc_type(mut self, name: Ident, ty: Type) -> Self {
+        self.types.push((name, ty));
+        self
+    }
+
+    /// Sets the value of the associated const `name`
+    pub fn assoc_const(mut self, name: Ident, expr: Expr) -> Self {
+        self.consts.push((name, expr));
+        self
+    }
+
+    /// Replaces `Self::Name` with the given associated type and other `Self` with the mock
+    fn replace_self(&self, tokens: TokenStream) -> Result<TokenStream> {
+        let mut replaced = TokenStream::new();
+        let mut tokens = tokens.into_iter();
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Ident(ident) if ident == "Self" => {
+                    let mut rest = tokens.clone();
+                    let projection = match (rest.next(), rest.next(), rest.next()) {
+                        (
+                            Some(TokenTree::Punct(a)),
+                            Some(TokenTree::Punct(b)),
+                            Some(TokenTree::Ident(name)),
+                        ) if a.as_char() == ':' && b.as_char() == ':' => Some(name),
+                        _ => None,
+                    };
+                    match projection {
+                        Some(name) => {
+                            let ty = self
+                                .types
+                                .iter()
+                                .find(|(ident, _)| *ident == name)
+                                .map(|(_, ty)| ty)
+                                .ok_or_else(|| {
+                                    Error::new_spanned(
+                                        &name,
+                                        format!("missing type for associated type `{}`", name),
+                                    )
+                                })?;
+                            ty.to_tokens(&mut replaced);
+                            tokens = rest;
+                        }
+                        None => self.ident.to_tokens(&mut replaced),
+                    }
+                }
+                TokenTree::Group(group) => {
+                    let mut inner =
+                        Group::new(group.delimiter(), self.replace_self(group.stream())?);
+                    inner.set_span(group.span());
+                    replaced.extend(Some(TokenTree::Group(inner)));
+                }
+                token => replaced.extend(Some(token)),
+            }
+        }
+        Ok(replaced)
+    }
+
+    fn mocked_fn(&self, f: &dyn FunctionLike) -> Result<MockedFn> {
+        let sig = named_sig(f.sig());
+        let ident = &sig.ident;
+        if sig.receiver().is_none() {
+            return Err(Error::new_spanned(
+                ident,
+                "associated functions without a receiver can't be mocked",
+            ));
+        }
+        let generic = sig
+            .generics
+            .params
+            .iter()
+            .any(|param| !matches!(param, GenericParam::Lifetime(_)));
+        let impl_trait = sig.inputs.iter().any(
+            |input| matches!(input, FnArg::Typed(arg) if matches!(*arg.ty, Type::ImplTrait(_))),
+        );
+        if generic || impl_trait {
+            return Err(Error::new_spanned(ident, "generic methods can't be mocked"));
+        }
+        let output = match &sig.output {
+            ReturnType::Default => quote!(()),
+            ReturnType::Type(_, ty) if borrows(ty.to_token_stream()) => {
+                return Err(Error::new_spanned(
+                    ty,
+                    "methods returning references can't be mocked",
+                ))
+            }
+            ReturnType::Type(_, ty) => self.replace_self(ty.to_token_stream())?,
+        };
+
+        let mut mocked = MockedFn {
+            ident: ident.clone(),
+            args: Vec::new(),
+            arg_tys: Vec::new(),
+            recorded_tys: Vec::new(),
+            recorded: Vec::new(),
+            output,
+        };
+        for input in &sig.inputs {
+            let arg = match input {
+                FnArg::Typed(arg) => arg,
+                FnArg::Receiver(_) => continue,
+            };
+            let ident = arg_ident(arg);
+            let (recorded_ty, recorded) = match &*arg.ty {
+                Type::Reference(ty) if !borrows(ty.elem.to_token_stream()) => {
+                    let elem = self.replace_self(ty.elem.to_token_stream())?;
+                    (
+                        quote!(<#elem as ::std::borrow::ToOwned>::Owned),
+                        quote!(::std::borrow::ToOwned::to_owned(&*#ident)),
+                    )
+                }
+                ty if borrows(ty.to_token_stream()) => {
+                    return Err(Error::new_spanned(
+                        ty,
+                        format!("argument `{}` borrows data and can't be recorded", ident),
+                    ))
+                }
+                ty => (
+                    self.replace_self(ty.to_token_stream())?,
+                    quote!(::std::clone::Clone::clone(&#ident)),
+                ),
+            };
+            mocked
+                .arg_tys
+                .push(self.replace_self(arg.ty.to_token_stream())?);
+            mocked.recorded_tys.push(recorded_ty);
+            mocked.recorded.push(recorded);
+            mocked.args.push(ident);
+        }
+        Ok(mocked)
+    }
+
+    /// Generates the mock struct, its inherent impl with `on_*` setters and the impl of `item`
+    pub fn build(&self, item: &ItemTrait) -> Result<Vec<Item>> {
+        if let Some(param) = item.generics.params.first() {
+            return Err(Error::new_spanned(param, "generic traits can't be mocked"));
+        }
+        let mut fns = Vec::new();
+        let mut errors: Vec<Error> = Vec::new();
+        for f in item.required_fns() {
+            match self.mocked_fn(f) {
+                Ok(f) => fns.push(f),
+                Err(e) => errors.push(e),
+            }
+        }
+        combine(errors)?;
+
+        let ident = &self.ident;
+        let vis = &item.vis;
+        let mut fields = Vec::new();
+        let mut setters = Vec::new();
+        let mut skeleton = ImplSkeleton::new(parse_quote!(#ident));
+        for (name, ty) in &self.types {
+            skeleton = skeleton.assoc_type(name.clone(), ty.clone());
+        }
+        for (name, expr) in &self.consts {
+            skeleton = skeleton.assoc_const(name.clone(), expr.clone());
+        }
+        for f in &fns {
+            let MockedFn {
+                ident: name,
+                args,
+                arg_tys,
+                recorded_tys,
+                recorded,
+                output,
+            } = f;
+            let calls = format_ident!("{}_calls", name);
+            let func = format_ident!("{}_fn", name);
+            let on = format_ident!("on_{}", name);
+            let closure_ty = quote!(dyn ::std::ops::FnMut(#(#arg_tys),*) -> #output);
+            fields.push(quote! {
+                pub #calls: ::std::cell::RefCell<::std::vec::Vec<(#(#recorded_tys,)*)>>,
+                pub #func: ::std::cell::RefCell<::std::option::Option<::std::boxed::Box<#closure_ty>>>,
+            });
+            setters.push(quote! {
+                pub fn #on(self, f: impl ::std::ops::FnMut(#(#arg_tys),*) -> #output + 'static) -> Self {
+                    *self.#func.borrow_mut() = ::std::option::Option::Some(::std::boxed::Box::new(f));
+                    self
+                }
+            });
+            let message = format!("{}::{} is called before `{}`", ident, name, on);
+            skeleton = skeleton.body(
+                name.clone(),
+                parse_quote!({
+                    self.#calls.borrow_mut().push((#(#recorded,)*));
+                    let mut f = self.#func.borrow_mut();
+                    let f = f.as_mut().expect(#message);
+                    f(#(#args),*)
+                }),
+            );
+        }
+        let mock: ItemStruct = parse_quote! {
+            #[derive(Default)]
+            #vis struct #ident {
+                #(#fields)*
+            }
+        };
+        let inherent: ItemImpl = parse_quote! {
+            impl #ident {
+                #(#setters)*
+            }
+        };
+        let generated = skeleton.build(item)?;
+        Ok(vec![
+            Item::Struct(mock),
+            Item::Impl(inherent),
+            Item::Impl(generated),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_quote_eq;
+
+    #[test]
+    fn test_build() {
+        let item: ItemTrait = parse_quote! {
+            pub trait Store {
+                type Key;
+                fn get(&self, key: &Self::Key) -> Option<String>;
+                fn set(&mut self, key: Self::Key, _: (u8, u8));
+                fn into_vec(self: Box<Self>) -> Vec<u8>;
+                fn len(&self) -> usize { 0 }
+            }
+        };
+        let generated = MockImpl::new(parse_quote!(MockStore))
+            .assoc_type(parse_quote!(Key), parse_quote!(String))
+            .build(&item)
+            .unwrap();
+        let expected = quote! {
+            #[derive(Default)]
+            pub struct MockStore {
+                pub get_calls: ::std::cell::RefCell<::std::vec::Vec<(<String as ::std::borrow::ToOwned>::Owned,)> >,
+                pub get_fn: ::std::cell::RefCell<::std::option::Option<::std::boxed::Box<dyn ::std::ops::FnMut(&String) -> Option<String> > > >,
+                pub set_calls: ::std::cell::RefCell<::std::vec::Vec<(String, (u8, u8),)> >,
+                pub set_fn: ::std::cell::RefCell<::std::option::Option<::std::boxed::Box<dyn ::std::ops::FnMut(String, (u8, u8)) -> ()> > >,
+                pub into_vec_calls: ::std::cell::RefCell<::std::vec::Vec<()> >,
+                pub into_vec_fn: ::std::cell::RefCell<::std::option::Option<::std::boxed::Box<dyn ::std::ops::FnMut() -> Vec<u8> > > >,
+            }
+            impl MockStore {
+                pub fn on_get(self, f: impl ::std::ops::FnMut(&String) -> Option<String> + 'static) -> Self {
+                    *self.get_fn.borrow_mut() = ::std::option::Option::Some(::std::boxed::Box::new(f));
+                    self
+                }
+                pub fn on_set(self, f: impl ::std::ops::FnMut(String, (u8, u8)) -> () + 'static) -> Self {
+                    *self.set_fn.borrow_mut() = ::std::option::Option::Some(::std::boxed::Box::new(f));
+                    self
+                }
+                pub fn on_into_vec(self, f: impl ::std::ops::FnMut() -> Vec<u8> + 'static) -> Self {
+                    *self.into_vec_fn.borrow_mut() = ::std::option::Option::Some(::std::boxed::Box::new(f));
+                    self
+                }
+            }
+            impl Store for MockStore {
+                type Key = String;
+                fn get(&self, key: &Self::Key) -> Option<String> {
+                    self.get_calls.borrow_mut().push((::std::borrow::ToOwned::to_owned(&*key),));
+                    let mut f = self.get_fn.borrow_mut();
+                    let f = f.as_mut().expect("MockStore::get is called before `on_get`");
+                    f(key)
+                }
+                fn set(&mut self, key: Self::Key, arg2: (u8, u8)) {
+                    self.set_calls.borrow_mut().push((::std::clone::Clone::clone(&key), ::std::clone::Clone::clone(&arg2),));
+                    let mut f = self.set_fn.borrow_mut();
+                    let f = f.as_mut().expect("MockStore::set is called before `on_set`");
+                    f(key, arg2)
+                }
+                fn into_vec(self: Box<Self>) -> Vec<u8> {
+                    self.into_vec_calls.borrow_mut().push(());
+                    let mut f = self.into_vec_fn.borrow_mut();
+                    let f = f.as_mut().expect("MockStore::into_vec is called before `on_into_vec`");
+                    f()
+                }
+            }
+        };
+        let generated = quote!(#(#generated)*);
+        assert_quote_eq!(generated, expected);
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let item: ItemTrait = parse_quote! {
+            trait A {
+                fn new() -> u8;
+                fn map<F: Fn()>(&self, f: F);
+                fn name(&self) -> &str;
+                fn push(&self, x: Option<&str>);
+            }
+        };
+        let err = MockImpl::new(parse_quote!(MockA))
+            .build(&item)
+            .err()
+            .unwrap();
+        let messages: Vec<_> = err.into_iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "associated functions without a receiver can't be mocked",
+                "generic methods can't be mocked",
+                "methods returning references can't be mocked",
+                "argument `x` borrows data and can't be recorded",
+            ]
+        );
+    }
+}
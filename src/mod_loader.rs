@@ -0,0 +1,254 @@
+use crate::item::{collect_items, ItemModExt};
+use std::path::{Path, PathBuf};
+use syn::ext::IdentExt;
+use syn::{AttrStyle, Error, Expr, ExprLit, File, Ident, Item, ItemMod, Lit, Meta, Result};
+
+/// Module tree of a crate loaded from the file system
+///
+/// Every `mod m;` declaration is expanded into an inline module with the content of
+/// `m.rs`, `m/mod.rs` or the file of `#[path = "..."]`.
+#[derive(Clone)]
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+pub struct ModuleTree {
+    /// Root file with expanded modules
+    pub file: File,
+    /// Source file of each loaded module. The root module has an empty path.
+    pub sources: Vec<(Vec<Ident>, PathBuf)>,
+}
+
+impl ModuleTree {
+    /// Loads the crate root file (e.g. `src/lib.rs`) and its out-of-line modules recursively
+    pub fn load<P>(root: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let root = root.as_ref();
+        let mut file = parse_file(root, None)?;
+        let dir = root.parent().unwrap_or_else(|| Path::new(""));
+        let mut sources = vec![(Vec::new(), root.to_owned())];
+        expand_items(&mut file.items, &mut Vec::new(), dir, dir, &mut sources)?;
+        Ok(Self { file, sources })
+    }
+
+    /// Returns the source file which contains the module of `path`
+    pub fn source_of(&self, path: &[Ident]) -> Option<&Path> {
+        self.sources
+            .iter()
+            .filter(|(module, _)| path.starts_with(module))
+            .max_by_key(|(module, _)| module.len())
+            .map(|(_, source)| source.as_path())
+    }
+
+    /// Returns every item in the tree with its module path and source file
+    pub fn items_with_sources(&self) -> Vec<(Vec<Ident>, &Path, &Item)> {
+        let mut found = Vec::new();
+        collect_items(&self.file.items, &mut Vec::new(), &mut |_| true, &mut found);
+        found
+            .into_iter()
+            .map(|(module, item)| {
+                let source = self.source_of(&module).expect("root is always a source");
+                (module, source, item)
+            })
+            .collect()
+    }
+}
+
+fn parse_file(path: &Path, decl: Option<&ItemMod>) -> Result<File> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        let message = format!("failed to read `{}`: {}", path.display(), e);
+        match decl {
+            Some(decl) => Error::new_spanned(decl, message),
+            None => Error::new(proc_macro2::Span::call_site(), message),
+        }
+    })?;
+    syn::parse_file(&content)
+        .map_err(|e| Error::new(e.span(), format!("{}: {}", path.display(), e)))
+}
+
+/// Returns the value of `#[path = "..."]`
+fn path_attr(module: &ItemMod) -> Result<Option<String>> {
+    for attr in &module.attrs {
+        if let Meta::NameValue(nv) = &attr.meta {
+            if !nv.path.is_ident("path") {
+                continue;
+            }
+            return match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit), ..
+                }) => Ok(Some(lit.value())),
+                other => Err(Error::new_spanned(other, "path expects string literal")),
+            };
+        }
+    }
+    Ok(None)
+}
+
+/// Expands module declarations of `items`.
+///
+/// `attr_dir` is the base of `#[path]` attributes and `dir` is the base of module files.
+fn expand_items(
+    items: &mut [Item],
+    module: &mut Vec<Ident>,
+    attr_dir: &Path,
+    dir: &Path,
+    sources: &mut Vec<(Vec<Ident>, PathBuf)>,
+) -> Result<()> {
+    for item in items {
+        let decl = match item {
+            Item::Mod(decl) => decl,
+            _ => continue,
+        };
+        let path = path_attr(decl)?;
+        module.push(decl.ident.clone());
+        if decl.content.is_some() {
+            let dir = match path {
+                Some(path) => dir.join(path),
+                None => dir.join(decl.ident.unraw().to_string()),
+            };
+            expand_items(decl.items_mut().unwrap(), module, &dir, &dir, sources)?;
+        } else {
+            let (source, mod_rs) = match path {
+                Some(path) => (attr_dir.join(path), true),
+                None => find_module_file(decl, dir)?,
+            };
+            let file = parse_file(&source, Some(decl))?;
+            let source_dir = source.parent().unwrap_or_else(|| Path::new(""));
+            let child_dir = if mod_rs {
+                source_dir.to_owned()
+            } else {
+                dir.join(decl.ident.unraw().to_string())
+            };
+            // inner attributes of the file are printed inside the braces of the module
+            decl.attrs.extend(file.attrs.into_iter().map(|mut attr| {
+                attr.style = AttrStyle::Inner(Default::default());
+                attr
+            }));
+            decl.content = Some((Default::default(), file.items));
+            decl.semi = None;
+            expand_items(
+                decl.items_mut().unwrap(),
+                module,
+                source_dir,
+                &child_dir,
+                sources,
+            )?;
+            sources.push((module.clone(), source));
+        }
+        module.pop();
+    }
+    Ok(())
+}
+
+/// Returns the file of `mod m;` and `true` if it is a `mod.rs` file
+fn find_module_file(decl: &ItemMod, dir: &Path) -> Result<(PathBuf, bool)> {
+    let name = decl.ident.unraw().to_string();
+    let file = dir.join(format!("{}.rs", name));
+    let mod_rs = dir.join(&name).join("mod.rs");
+    match (file.is_file(), mod_rs.is_file()) {
+        (true, false) => Ok((file, false)),
+        (false, true) => Ok((mod_rs, true)),
+        (true, true) => Err(Error::new_spanned(
+            &decl.ident,
+            format!(
+                "file for module `{}` found at both `{}` and `{}`",
+                name,
+                file.display(),
+                mod_rs.display()
+            ),
+        )),
+        (false, false) => Err(Error::new_spanned(
+            &decl.ident,
+            format!(
+                "file not found for module `{}`; expected `{}` or `{}`",
+                name,
+                file.display(),
+                mod_rs.display()
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ident::GetIdent;
+    use quote::quote;
+
+    fn write_crate(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("syn-ext-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for (path, content) in files {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_load() {
+        let root = write_crate(
+            "load",
+            &[
+                (
+                    "lib.rs",
+                    "mod a; mod c; #[path = \"other/custom.rs\"] mod d; mod r#type;",
+                ),
+                ("a.rs", "#![allow(dead_code)] mod b; struct A;"),
+                ("a/b.rs", "struct B;"),
+                ("c/mod.rs", "mod inline { mod e; }"),
+                ("c/inline/e.rs", "struct E;"),
+                ("other/custom.rs", "mod f;"),
+                ("other/f.rs", "struct F;"),
+                ("type.rs", "mod r#struct;"),
+                ("type/struct.rs", "struct S;"),
+            ],
+        );
+        let tree = ModuleTree::load(root.join("lib.rs")).unwrap();
+        let items: Vec<_> = tree
+            .items_with_sources()
+            .into_iter()
+            .filter(|(_, _, item)| matches!(item, Item::Struct(_)))
+            .map(|(module, source, item)| {
+                let ident = item.get_ident().unwrap();
+                let source = source.strip_prefix(&root).unwrap().to_owned();
+                (
+                    quote!(#(#module::)* #ident).to_string(),
+                    source.to_string_lossy().into_owned(),
+                )
+            })
+            .collect();
+        let expected = [
+            ("a :: b :: B", "a/b.rs"),
+            ("a :: A", "a.rs"),
+            ("c :: inline :: e :: E", "c/inline/e.rs"),
+            ("d :: f :: F", "other/f.rs"),
+            ("r#type :: r#struct :: S", "type/struct.rs"),
+        ];
+        assert_eq!(items.len(), expected.len());
+        for ((item, source), (expected_item, expected_source)) in items.iter().zip(&expected) {
+            assert_eq!(item, expected_item);
+            assert_eq!(Path::new(source), Path::new(expected_source));
+        }
+
+        let a = match &tree.file.items[0] {
+            Item::Mod(a) => a,
+            _ => unreachable!(),
+        };
+        assert!(a.items().is_some());
+        assert_eq!(a.attrs.len(), 1);
+        assert_eq!(
+            quote!(#a).to_string(),
+            quote! { mod a { #![allow(dead_code)] mod b { struct B; } struct A; } }.to_string()
+        );
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_load_not_found() {
+        let root = write_crate("not-found", &[("lib.rs", "mod a;")]);
+        let err = ModuleTree::load(root.join("lib.rs")).err().unwrap();
+        assert!(err.to_string().starts_with("file not found for module `a`"));
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
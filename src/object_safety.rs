@@ -0,0 +1,256 @@
+use crate::error::combine;
+use crate::item::{has_self_sized, ItemLike};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::visit::Visit;
+use syn::{
+    Error, FnArg, GenericParam, ItemTrait, Result, ReturnType, TraitBoundModifier, Type,
+    TypeImplTrait, TypeParamBound,
+};
+
+/// Returns the first `Self` in `tokens` which is not a projection like `Self::Item`
+pub(crate) fn find_bare_self(tokens: TokenStream) -> Option<TokenTree> {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match &token {
+            TokenTree::Ident(ident) if ident == "Self" => {
+                let projected = match tokens.peek() {
+                    Some(TokenTree::Punct(punct)) => punct.as_char() == ':',
+                    Some(TokenTree::Ident(ident)) => ident == "as",
+                    _ => false,
+                };
+                if !projected {
+                    return Some(token);
+                }
+            }
+            TokenTree::Group(group) => {
+                if let Some(found) = find_bare_self(group.stream()) {
+                    return Some(found);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Finds the first `impl Trait` in a type, e.g. in `&impl Fn()` or `Box<impl Trait>`
+#[derive(Default)]
+struct ImplTraitFinder<'ast> {
+    found: Option<&'ast TypeImplTrait>,
+}
+
+impl<'ast> Visit<'ast> for ImplTraitFinder<'ast> {
+    fn visit_type_impl_trait(&mut self, ty: &'ast TypeImplTrait) {
+        self.found.get_or_insert(ty);
+    }
+}
+
+fn find_impl_trait(ty: &Type) -> Option<&TypeImplTrait> {
+    let mut finder = ImplTraitFinder::default();
+    finder.visit_type(ty);
+    finder.found
+}
+
+/// Object safety, a.k.a. dyn compatibility, analysis of [syn::ItemTrait]
+pub trait ObjectSafetyExt {
+    /// Returns a diagnostic for each reason `dyn Trait` can't be made.
+    ///
+    /// Methods with `where Self: Sized` are excluded from trait objects and never reported.
+    fn object_safety_violations(&self) -> Vec<Error>;
+
+    /// Returns `true` if `dyn Trait` can be made
+    fn is_object_safe(&self) -> bool {
+        self.object_safety_violations().is_empty()
+    }
+
+    /// Returns every violation combined into a single error
+    fn check_object_safety(&self) -> Result<()> {
+        combine(self.object_safety_violations())
+    }
+}
+
+impl ObjectSafetyExt for ItemTrait {
+    fn object_safety_violations(&self) -> Vec<Error> {
+        let error = |tokens: &dyn ToTokens, reason: String| {
+            Error::new_spanned(
+                tokens,
+                format!("`{}` is not dyn compatible because {}", self.ident, reason),
+            )
+        };
+        let mut violations = Vec::new();
+
+        for bound in &self.supertraits {
+            if let TypeParamBound::Trait(bound) = bound {
+                let sized = matches!(bound.modifier, TraitBoundModifier::None)
+                    && bound
+                        .path
+                        .segments
+                        .last()
+                        .is_some_and(|segment| segment.ident == "Sized");
+                if sized {
+                    violations.push(error(bound, "it requires `Self: Sized`".to_owned()));
+                }
+            }
+        }
+        if let Some(where_clause) = &self.generics.where_clause {
+            if has_self_sized(where_clause) {
+                violations.push(error(where_clause, "it requires `Self: Sized`".to_owned()));
+            }
+        }
+
+        for item in &self.items {
+            if let Ok(c) = item.constant() {
+                violations.push(error(
+                    c.ident(),
+                    format!("it has associated const `{}`", c.ident()),
+                ));
+                continue;
+            }
+            if let Ok(t) = item.type_alias() {
+                let generic = !t.generics().params.is_empty()
+                    && !t
+                        .generics()
+                        .where_clause
+                        .as_ref()
+                        .is_some_and(has_self_sized);
+                if generic {
+                    violations.push(error(
+                        t.ident(),
+                        format!("it has generic associated type `{}`", t.ident()),
+                    ));
+                }
+                continue;
+            }
+            let f = match item.function_or_method() {
+                Ok(f) if !f.has_sized_escape() => f,
+                _ => continue,
+            };
+            let sig = f.sig();
+            let ident = &sig.ident;
+            if sig.receiver().is_none() {
+                violations.push(error(
+                    ident,
+                    format!("associated function `{}` has no `self` parameter", ident),
+                ));
+                continue;
+            }
+            if let Some(asyncness) = &sig.asyncness {
+                violations.push(error(asyncness, format!("method `{}` is async", ident)));
+            }
+            let generic = sig
+                .generics
+                .params
+                .iter()
+                .find(|param| !matches!(param, GenericParam::Lifetime(_)));
+            if let Some(param) = generic {
+                violations.push(error(
+                    param,
+                    format!("method `{}` has generic type parameters", ident),
+                ));
+            }
+            for input in &sig.inputs {
+                let arg = match input {
+                    FnArg::Typed(arg) => arg,
+                    FnArg::Receiver(_) => continue,
+                };
+                if let Some(ty) = find_impl_trait(&arg.ty) {
+                    violations.push(error(
+                        ty,
+                        format!("method `{}` has generic type parameters", ident),
+                    ));
+                } else if let Some(token) = find_bare_self(arg.ty.to_token_stream()) {
+                    violations.push(error(
+                        &token,
+                        format!("method `{}` references `Self` in its parameters", ident),
+                    ));
+                }
+            }
+            if let ReturnType::Type(_, ty) = &sig.output {
+                if let Some(impl_trait) = find_impl_trait(ty) {
+                    violations.push(error(
+                        impl_trait,
+                        format!("method `{}` returns `impl Trait`", ident),
+                    ));
+                } else if let Some(token) = find_bare_self(ty.to_token_stream()) {
+                    violations.push(error(
+                        &token,
+                        format!("method `{}` references `Self` in its return type", ident),
+                    ));
+                }
+            }
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_object_safe() {
+        let item: ItemTrait = parse_quote! {
+            trait A: Clone {
+                type Output;
+                fn get(&self) -> Self::Output;
+                fn cast(&self) -> <Self as A>::Output;
+                fn boxed(self: Box<Self>);
+                fn new() -> Self where Self: Sized;
+                fn map<F: Fn()>(self, f: F) where Self: Sized;
+            }
+        };
+        assert!(item.is_object_safe());
+        assert!(item.check_object_safety().is_ok());
+    }
+
+    #[test]
+    fn test_violations() {
+        let item: ItemTrait = parse_quote! {
+            trait A: Sized {
+                const N: usize;
+                fn new() -> u8;
+                fn map<F: Fn()>(&self, f: F);
+                fn apply(&self, f: impl Fn());
+                fn eq(&self, other: &Self) -> bool;
+                fn dup(&self) -> Option<Self>;
+                fn call(&self, f: &impl Fn());
+                fn boxed(&self, f: Box<impl Fn()>);
+                async fn run(&self);
+                fn iter(&self) -> Box<impl Iterator>;
+                type Item<'a>;
+                type Sized<T> where Self: Sized;
+            }
+        };
+        assert!(!item.is_object_safe());
+        let messages: Vec<_> = item
+            .check_object_safety()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "`A` is not dyn compatible because it requires `Self: Sized`",
+                "`A` is not dyn compatible because it has associated const `N`",
+                "`A` is not dyn compatible because associated function `new` has no `self` parameter",
+                "`A` is not dyn compatible because method `map` has generic type parameters",
+                "`A` is not dyn compatible because method `apply` has generic type parameters",
+                "`A` is not dyn compatible because method `eq` references `Self` in its parameters",
+                "`A` is not dyn compatible because method `dup` references `Self` in its return type",
+                "`A` is not dyn compatible because method `call` has generic type parameters",
+                "`A` is not dyn compatible because method `boxed` has generic type parameters",
+                "`A` is not dyn compatible because method `run` is async",
+                "`A` is not dyn compatible because method `iter` returns `impl Trait`",
+                "`A` is not dyn compatible because it has generic associated type `Item`",
+            ]
+        );
+
+        let item: ItemTrait = parse_quote! {
+            trait B where Self: Sized {}
+        };
+        assert_eq!(item.object_safety_violations().len(), 1);
+    }
+}
@@ -0,0 +1,366 @@
+use crate::attribute::AttributeExt;
+use crate::error::combine;
+#[cfg(feature = "full")]
+use crate::item::ItemLike;
+use crate::meta::{Meta1, NestedMeta};
+use syn::{Attribute, Data, DeriveInput, Error, Fields, Path, Result};
+
+/// Node kinds where attributes can be placed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AttrTarget {
+    /// Struct, enum, union or any other item the macro is applied to
+    Container,
+    Field,
+    Variant,
+    Fn,
+    ImplItem,
+    TraitItem,
+    /// Parameter of a function
+    Param,
+}
+
+impl AttrTarget {
+    /// Returns the human-readable name of the target
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Container => "container",
+            Self::Field => "field",
+            Self::Variant => "variant",
+            Self::Fn => "function",
+            Self::ImplItem => "impl item",
+            Self::TraitItem => "trait item",
+            Self::Param => "parameter",
+        }
+    }
+}
+
+struct Rule {
+    path: String,
+    key: Option<String>,
+    targets: Vec<AttrTarget>,
+}
+
+/// Declaration of attributes allowed on each node kind
+///
+/// Only declared attribute paths are validated; others like `#[doc]` are left to the compiler.
+/// When keys are declared for a path, every key in its list must be declared too.
+#[derive(Default)]
+pub struct AttrPlacement {
+    rules: Vec<Rule>,
+}
+
+fn path_key(path: &Path) -> String {
+    let segments: Vec<_> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    segments.join("::")
+}
+
+fn nested_path(nested: &NestedMeta) -> Option<&Path> {
+    match nested {
+        NestedMeta::Meta(meta) => Some(meta.path()),
+        NestedMeta::Lit(_) => None,
+    }
+}
+
+/// Returns `true` if any of `rules` allows `target`
+fn allows<'a>(mut rules: impl Iterator<Item = &'a &'a Rule>, target: AttrTarget) -> bool {
+    rules.any(|rule| rule.targets.contains(&target))
+}
+
+/// Returns the targets allowed by any of `rules`, like `field or variant`
+fn expected_targets<'a>(rules: impl Iterator<Item = &'a &'a Rule>) -> String {
+    let mut names: Vec<_> = Vec::new();
+    for target in rules.flat_map(|rule| &rule.targets) {
+        if !names.contains(&target.name()) {
+            names.push(target.name());
+        }
+    }
+    names.join(" or ")
+}
+
+impl AttrPlacement {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows `#[path ...]` on `targets`
+    pub fn allow(mut self, path: &str, targets: &[AttrTarget]) -> Self {
+        self.rules.push(Rule {
+            path: path.to_owned(),
+            key: None,
+            targets: targets.to_vec(),
+        });
+        self
+    }
+
+    /// Allows `key` of `#[path(key, ...)]` on `targets`
+    pub fn allow_key(mut self, path: &str, key: &str, targets: &[AttrTarget]) -> Self {
+        self.rules.push(Rule {
+            path: path.to_owned(),
+            key: Some(key.to_owned()),
+            targets: targets.to_vec(),
+        });
+        self
+    }
+
+    /// Checks `attrs` placed on `target` and pushes an error for each misplaced attribute
+    pub fn check_attrs(&self, attrs: &[Attribute], target: AttrTarget, errors: &mut Vec<Error>) {
+        for attr in attrs {
+            let path = path_key(attr.path());
+            let rules: Vec<_> = self.rules.iter().filter(|r| r.path == path).collect();
+            if rules.is_empty() {
+                continue;
+            }
+            let path_rules = rules.iter().filter(|r| r.key.is_none());
+            if path_rules.clone().next().is_some() && !allows(path_rules.clone(), target) {
+                errors.push(Error::new_spanned(
+                    attr,
+                    format!(
+                        "attribute `{}` is not allowed here; expected on {}, found on {}",
+                        path,
+                        expected_targets(path_rules),
+                        target.name()
+                    ),
+                ));
+                continue;
+            }
+            if rules.iter().all(|r| r.key.is_none()) {
+                continue;
+            }
+            let list = match attr.parse_meta() {
+                Ok(Meta1::List(list)) => list,
+                Ok(_) => continue,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+            for nested in &list.nested {
+                let key = match nested_path(nested) {
+                    Some(key) => path_key(key),
+                    None => {
+                        errors.push(Error::new_spanned(nested, "expected attribute key"));
+                        continue;
+                    }
+                };
+                let key_rules = rules.iter().filter(|r| r.key.as_ref() == Some(&key));
+                if key_rules.clone().next().is_none() {
+                    errors.push(Error::new_spanned(
+                        nested,
+                        format!("unknown attribute `{}({})`", path, key),
+                    ));
+                } else if !allows(key_rules.clone(), target) {
+                    errors.push(Error::new_spanned(
+                        nested,
+                        format!(
+                            "attribute `{}({})` is not allowed here; expected on {}, found on {}",
+                            path,
+                            key,
+                            expected_targets(key_rules),
+                            target.name()
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn check_fields(&self, fields: &Fields, errors: &mut Vec<Error>) {
+        for field in fields {
+            self.check_attrs(&field.attrs, AttrTarget::Field, errors);
+        }
+    }
+
+    fn check_data(&self, data: &Data, errors: &mut Vec<Error>) {
+        match data {
+            Data::Struct(data) => self.check_fields(&data.fields, errors),
+            Data::Enum(data) => {
+                for variant in &data.variants {
+                    self.check_attrs(&variant.attrs, AttrTarget::Variant, errors);
+                    self.check_fields(&variant.fields, errors);
+                }
+            }
+            Data::Union(data) => {
+                for field in &data.fields.named {
+                    self.check_attrs(&field.attrs, AttrTarget::Field, errors);
+                }
+            }
+        }
+    }
+
+    /// Validates attributes of the container, its variants and its fields
+    pub fn validate_derive_input(&self, input: &DeriveInput) -> Result<()> {
+        let mut errors = Vec::new();
+        self.check_attrs(&input.attrs, AttrTarget::Container, &mut errors);
+        self.check_data(&input.data, &mut errors);
+        combine(errors)
+    }
+
+    #[cfg(feature = "full")]
+    fn check_sig(&self, sig: &syn::Signature, errors: &mut Vec<Error>) {
+        for input in &sig.inputs {
+            let attrs = match input {
+                syn::FnArg::Receiver(receiver) => &receiver.attrs,
+                syn::FnArg::Typed(pat) => &pat.attrs,
+            };
+            self.check_attrs(attrs, AttrTarget::Param, errors);
+        }
+    }
+
+    #[cfg(feature = "full")]
+    fn check_item_like<I>(&self, item: &I, target: AttrTarget, errors: &mut Vec<Error>)
+    where
+        I: ItemLike,
+    {
+        // verbatim items have no attributes to check
+        if let Ok(attrs) = item.attrs() {
+            self.check_attrs(attrs, target, errors);
+        }
+        if let Ok(function) = item.function_or_method() {
+            self.check_sig(function.sig(), errors);
+        }
+    }
+
+    /// Validates attributes of an item and its nested nodes.
+    ///
+    /// Functions are [AttrTarget::Fn]; items of `impl` and `trait` blocks are
+    /// [AttrTarget::ImplItem] and [AttrTarget::TraitItem]; any other item is
+    /// [AttrTarget::Container].
+    #[cfg(feature = "full")]
+    pub fn validate_item(&self, item: &syn::Item) -> Result<()> {
+        use syn::Item;
+
+        let mut errors = Vec::new();
+        let target = match item {
+            Item::Fn(_) => AttrTarget::Fn,
+            _ => AttrTarget::Container,
+        };
+        self.check_item_like(item, target, &mut errors);
+        match item {
+            Item::Struct(item) => self.check_fields(&item.fields, &mut errors),
+            Item::Enum(item) => {
+                for variant in &item.variants {
+                    self.check_attrs(&variant.attrs, AttrTarget::Variant, &mut errors);
+                    self.check_fields(&variant.fields, &mut errors);
+                }
+            }
+            Item::Union(item) => {
+                for field in &item.fields.named {
+                    self.check_attrs(&field.attrs, AttrTarget::Field, &mut errors);
+                }
+            }
+            Item::Impl(item) => {
+                for impl_item in &item.items {
+                    self.check_item_like(impl_item, AttrTarget::ImplItem, &mut errors);
+                }
+            }
+            Item::Trait(item) => {
+                for trait_item in &item.items {
+                    self.check_item_like(trait_item, AttrTarget::TraitItem, &mut errors);
+                }
+            }
+            _ => {}
+        }
+        combine(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn placement() -> AttrPlacement {
+        use AttrTarget::*;
+        AttrPlacement::new()
+            .allow("container_only", &[Container])
+            .allow_key("my", "rename_all", &[Container])
+            .allow_key("my", "rename", &[Field, Variant])
+            .allow_key("my", "skip", &[Field, Param])
+    }
+
+    fn messages(error: Error) -> Vec<String> {
+        error.into_iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn test_validate_derive_input() {
+        let input: DeriveInput = parse_quote! {
+            #[my(rename_all = "snake_case")]
+            #[doc = "not validated"]
+            enum A {
+                #[my(rename = "b")]
+                B {
+                    #[my(skip)]
+                    x: u8,
+                },
+            }
+        };
+        placement().validate_derive_input(&input).unwrap();
+
+        let input: DeriveInput = parse_quote! {
+            #[my(skip, unknown)]
+            struct A {
+                #[container_only]
+                #[my(rename_all = "x")]
+                x: u8,
+            }
+        };
+        let errors = messages(placement().validate_derive_input(&input).unwrap_err());
+        assert_eq!(
+            errors,
+            [
+                "attribute `my(skip)` is not allowed here; expected on field or parameter, found on container",
+                "unknown attribute `my(unknown)`",
+                "attribute `container_only` is not allowed here; expected on container, found on field",
+                "attribute `my(rename_all)` is not allowed here; expected on container, found on field",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merged_rules() {
+        use AttrTarget::*;
+        let placement = AttrPlacement::new()
+            .allow("x", &[Container])
+            .allow("x", &[Field])
+            .allow_key("my", "skip", &[Field])
+            .allow_key("my", "skip", &[Variant]);
+        let input: DeriveInput = parse_quote! {
+            #[x]
+            enum A {
+                #[my(skip)]
+                B {
+                    #[x]
+                    #[my(skip)]
+                    y: u8,
+                },
+                #[x]
+                C,
+            }
+        };
+        let errors = messages(placement.validate_derive_input(&input).unwrap_err());
+        assert_eq!(
+            errors,
+            ["attribute `x` is not allowed here; expected on container or field, found on variant"]
+        );
+    }
+
+    #[cfg(feature = "full")]
+    #[test]
+    fn test_validate_item() {
+        let item: syn::Item = parse_quote! {
+            impl A {
+                #[container_only]
+                fn f(&self, #[my(skip)] x: u8, #[my(rename = "y")] y: u8) {}
+            }
+        };
+        let errors = messages(placement().validate_item(&item).unwrap_err());
+        assert_eq!(
+            errors,
+            [
+                "attribute `container_only` is not allowed here; expected on container, found on impl item",
+                "attribute `my(rename)` is not allowed here; expected on field or variant, found on parameter",
+            ]
+        );
+    }
+}
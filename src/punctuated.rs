@@ -1,11 +1,96 @@
+use std::ops::{Bound, RangeBounds};
 use syn::punctuated::{Pair, Punctuated};
 
 /// Extension for [syn::punctuated::Punctuated]
+///
+/// Every operation keeps the original punctuation of the remaining elements and
+/// the trailing punctuation state of the list.
+/// `P::default()` is only used when an element without punctuation is moved before another.
 pub trait PunctuatedExt<T, P> {
-    /// Removes and returns the element at position index, popping all elements after it and push them back.
+    /// Removes and returns the element at position index with its punctuation.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
     fn remove(&mut self, index: usize) -> Pair<T, P>
     where
         P: Default;
+
+    /// Inserts a pair at position index, keeping its punctuation if given.
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than the length.
+    fn insert_pair(&mut self, index: usize, pair: Pair<T, P>)
+    where
+        P: Default;
+
+    /// Swaps two elements. Punctuations stay at their positions.
+    ///
+    /// # Panics
+    /// Panics if `a` or `b` is out of bounds.
+    fn swap(&mut self, a: usize, b: usize);
+
+    /// Replaces elements of `range` with `values` and returns the removed pairs.
+    ///
+    /// New values are punctuated by `P::default()`.
+    fn splice<R, I>(&mut self, range: R, values: I) -> Vec<Pair<T, P>>
+    where
+        P: Default,
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>;
+
+    /// Removes elements of `range` and returns them as pairs.
+    fn drain<R>(&mut self, range: R) -> Vec<Pair<T, P>>
+    where
+        P: Default,
+        R: RangeBounds<usize>;
+
+    /// Splits the list into two at the given index. Both keep the trailing punctuation state.
+    ///
+    /// # Panics
+    /// Panics if `at` is greater than the length.
+    fn split_off(&mut self, at: usize) -> Self
+    where
+        P: Default;
+
+    /// Retains only the elements specified by the predicate.
+    fn retain<F>(&mut self, f: F)
+    where
+        P: Default,
+        F: FnMut(&T) -> bool;
+
+    /// Sorts the elements with a key extraction function. Punctuations stay at their positions.
+    fn sort_by_key<K, F>(&mut self, f: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K;
+
+    /// Removes consecutive elements that resolve to the same key, like [Vec::dedup_by_key].
+    fn dedup_by_key<K, F>(&mut self, f: F)
+    where
+        P: Default,
+        K: PartialEq,
+        F: FnMut(&T) -> K;
+
+    /// Retains elements for which `f` returns `true` and returns the others as a new list.
+    ///
+    /// Both keep the trailing punctuation state.
+    fn partition<F>(&mut self, f: F) -> Self
+    where
+        P: Default,
+        F: FnMut(&T) -> bool;
+
+    /// Appends values whose keys are not in the list yet and returns the number of appended values.
+    fn extend_unique<I, K, F>(&mut self, values: I, f: F) -> usize
+    where
+        P: Default,
+        I: IntoIterator<Item = T>,
+        K: PartialEq,
+        F: FnMut(&T) -> K;
+
+    /// Searches for an element satisfying the predicate and returns it with its index
+    fn find_position<F>(&self, f: F) -> Option<(usize, &T)>
+    where
+        F: FnMut(&T) -> bool;
 }
 
 impl<T, P> PunctuatedExt<T, P> for Punctuated<T, P> {
@@ -13,16 +98,251 @@ impl<T, P> PunctuatedExt<T, P> for Punctuated<T, P> {
     where
         P: Default,
     {
-        let mut stack = Vec::new();
-        for _ in index + 1..self.len() {
-            stack.push(self.pop().unwrap().into_value());
-        }
-        let removed = self.pop().unwrap();
-        while let Some(item) = stack.pop() {
-            self.push(item)
+        let len = self.len();
+        assert!(
+            index < len,
+            "removal index (is {}) should be < len (is {})",
+            index,
+            len
+        );
+        let trailing = self.trailing_punct();
+        let mut tail = take_tail(self, index);
+        let removed = tail.remove(0);
+        push_pairs(self, tail, trailing);
+        removed
+    }
+
+    fn insert_pair(&mut self, index: usize, pair: Pair<T, P>)
+    where
+        P: Default,
+    {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "insertion index (is {}) should be <= len (is {})",
+            index,
+            len
+        );
+        let trailing = self.trailing_punct();
+        let tail = take_tail(self, index);
+        push_pairs(self, std::iter::once(pair).chain(tail), trailing);
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        let len = self.len();
+        assert!(
+            a < len && b < len,
+            "swap indices (are {} and {}) should be < len (is {})",
+            a,
+            b,
+            len
+        );
+        let (a, b) = if a <= b { (a, b) } else { (b, a) };
+        let mut values: Vec<&mut T> = self.iter_mut().collect();
+        let (head, tail) = values.split_at_mut(b);
+        if a != b {
+            std::mem::swap(head[a], tail[0]);
         }
+    }
+
+    fn splice<R, I>(&mut self, range: R, values: I) -> Vec<Pair<T, P>>
+    where
+        P: Default,
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len,
+        };
+        assert!(
+            start <= end && end <= len,
+            "range {}..{} out of bounds for len {}",
+            start,
+            end,
+            len
+        );
+        let trailing = self.trailing_punct();
+        let mut tail = take_tail(self, start);
+        let removed = tail.drain(..end - start).collect();
+        let values = values
+            .into_iter()
+            .map(|value| Pair::Punctuated(value, P::default()));
+        push_pairs(self, values.chain(tail), trailing);
         removed
     }
+
+    fn drain<R>(&mut self, range: R) -> Vec<Pair<T, P>>
+    where
+        P: Default,
+        R: RangeBounds<usize>,
+    {
+        self.splice(range, std::iter::empty())
+    }
+
+    fn split_off(&mut self, at: usize) -> Self
+    where
+        P: Default,
+    {
+        let len = self.len();
+        assert!(
+            at <= len,
+            "`at` split index (is {}) should be <= len (is {})",
+            at,
+            len
+        );
+        let trailing = self.trailing_punct();
+        let tail = take_tail(self, at);
+        push_pairs(self, Vec::new(), trailing);
+        from_pairs(tail, trailing)
+    }
+
+    fn retain<F>(&mut self, mut f: F)
+    where
+        P: Default,
+        F: FnMut(&T) -> bool,
+    {
+        let (mut pairs, trailing) = take_pairs(self);
+        pairs.retain(|pair| f(pair.value()));
+        *self = from_pairs(pairs, trailing);
+    }
+
+    fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        let (pairs, _) = take_pairs(self);
+        let (mut values, puncts): (Vec<_>, Vec<_>) =
+            pairs.into_iter().map(Pair::into_tuple).unzip();
+        values.sort_by_key(|value| f(value));
+        *self = values
+            .into_iter()
+            .zip(puncts)
+            .map(|(value, punct)| Pair::new(value, punct))
+            .collect();
+    }
+
+    fn dedup_by_key<K, F>(&mut self, mut f: F)
+    where
+        P: Default,
+        K: PartialEq,
+        F: FnMut(&T) -> K,
+    {
+        let (mut pairs, trailing) = take_pairs(self);
+        pairs.dedup_by_key(|pair| f(pair.value()));
+        *self = from_pairs(pairs, trailing);
+    }
+
+    fn partition<F>(&mut self, mut f: F) -> Self
+    where
+        P: Default,
+        F: FnMut(&T) -> bool,
+    {
+        let (pairs, trailing) = take_pairs(self);
+        let (retained, others): (Vec<_>, Vec<_>) =
+            pairs.into_iter().partition(|pair| f(pair.value()));
+        *self = from_pairs(retained, trailing);
+        from_pairs(others, trailing)
+    }
+
+    fn extend_unique<I, K, F>(&mut self, values: I, mut f: F) -> usize
+    where
+        P: Default,
+        I: IntoIterator<Item = T>,
+        K: PartialEq,
+        F: FnMut(&T) -> K,
+    {
+        let (mut pairs, trailing) = take_pairs(self);
+        let mut keys: Vec<K> = pairs.iter().map(|pair| f(pair.value())).collect();
+        let len = pairs.len();
+        for value in values {
+            let key = f(&value);
+            if !keys.contains(&key) {
+                keys.push(key);
+                pairs.push(Pair::End(value));
+            }
+        }
+        let appended = pairs.len() - len;
+        *self = from_pairs(pairs, trailing);
+        appended
+    }
+
+    fn find_position<F>(&self, mut f: F) -> Option<(usize, &T)>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter().enumerate().find(|(_, value)| f(value))
+    }
+}
+
+/// Takes all pairs out of `punctuated` with its trailing punctuation state
+pub(crate) fn take_pairs<T, P>(punctuated: &mut Punctuated<T, P>) -> (Vec<Pair<T, P>>, bool) {
+    let trailing = punctuated.trailing_punct();
+    let pairs = std::mem::take(punctuated).into_pairs().collect();
+    (pairs, trailing)
+}
+
+/// Pops the pairs from `at` to the end of `punctuated`, in order
+fn take_tail<T, P>(punctuated: &mut Punctuated<T, P>, at: usize) -> Vec<Pair<T, P>> {
+    let mut tail = Vec::new();
+    while punctuated.len() > at {
+        tail.push(punctuated.pop().unwrap());
+    }
+    tail.reverse();
+    tail
+}
+
+/// Pushes `pairs` back to `punctuated` and restores its trailing punctuation state
+fn push_pairs<T, P, I>(punctuated: &mut Punctuated<T, P>, pairs: I, trailing: bool)
+where
+    P: Default,
+    I: IntoIterator<Item = Pair<T, P>>,
+{
+    for pair in pairs {
+        if !punctuated.empty_or_trailing() {
+            punctuated.push_punct(P::default());
+        }
+        let (value, punct) = pair.into_tuple();
+        punctuated.push_value(value);
+        if let Some(punct) = punct {
+            punctuated.push_punct(punct);
+        }
+    }
+    if trailing && !punctuated.empty_or_trailing() {
+        punctuated.push_punct(P::default());
+    } else if !trailing && punctuated.trailing_punct() {
+        let value = punctuated.pop().unwrap().into_value();
+        punctuated.push_value(value);
+    }
+}
+
+/// Constructs a [Punctuated] from pairs, fixing up punctuation of the last element
+/// and the elements moved before others.
+pub(crate) fn from_pairs<T, P>(pairs: Vec<Pair<T, P>>, trailing: bool) -> Punctuated<T, P>
+where
+    P: Default,
+{
+    let len = pairs.len();
+    pairs
+        .into_iter()
+        .enumerate()
+        .map(|(i, pair)| {
+            let (value, punct) = pair.into_tuple();
+            if i + 1 < len || trailing {
+                Pair::Punctuated(value, punct.unwrap_or_default())
+            } else {
+                Pair::End(value)
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -30,7 +350,8 @@ impl<T, P> PunctuatedExt<T, P> for Punctuated<T, P> {
 mod test {
     use super::*;
     use crate::assert_quote_eq;
-    use crate::meta::MetaList1;
+    use crate::ident::GetIdent;
+    use crate::meta::{MetaList1, NestedMeta};
     use syn::parse_quote;
 
     #[test]
@@ -43,4 +364,116 @@ mod test {
         let expected: MetaList1 = parse_quote!(meta(b, d));
         assert_quote_eq!(list, expected);
     }
+
+    #[test]
+    fn test_remove_trailing() {
+        let mut list: MetaList1 = parse_quote!(meta(a, b, c,));
+        list.nested.remove(2);
+        let expected: MetaList1 = parse_quote!(meta(a, b,));
+        assert_quote_eq!(list, expected);
+        let mut list: MetaList1 = parse_quote!(meta(a, b, c));
+        list.nested.remove(2);
+        let expected: MetaList1 = parse_quote!(meta(a, b));
+        assert_quote_eq!(list, expected);
+    }
+
+    #[test]
+    fn test_insert_pair_and_swap() {
+        let mut list: MetaList1 = parse_quote!(meta(a, c));
+        list.nested.insert_pair(1, Pair::End(parse_quote!(b)));
+        list.nested.insert_pair(3, Pair::End(parse_quote!(d)));
+        let expected: MetaList1 = parse_quote!(meta(a, b, c, d));
+        assert_quote_eq!(list, expected);
+        list.nested.swap(3, 0);
+        let expected: MetaList1 = parse_quote!(meta(d, b, c, a));
+        assert_quote_eq!(list, expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_swap_out_of_bounds() {
+        let mut list: MetaList1 = parse_quote!(meta(a, b));
+        list.nested.swap(2, 2);
+    }
+
+    #[test]
+    fn test_splice_drain_split_off() {
+        let mut list: MetaList1 = parse_quote!(meta(a, b, c, d,));
+        let removed = list.nested.splice(1..3, vec![parse_quote!(x)]);
+        assert_eq!(removed.len(), 2);
+        let expected: MetaList1 = parse_quote!(meta(a, x, d,));
+        assert_quote_eq!(list, expected);
+        list.nested.splice(3.., vec![parse_quote!(e)]);
+        let expected: MetaList1 = parse_quote!(meta(a, x, d, e,));
+        assert_quote_eq!(list, expected);
+        list.nested.drain(3..=3);
+
+        let drained = list.nested.drain(2..);
+        assert!(drained[0].punct().is_some());
+        let expected: MetaList1 = parse_quote!(meta(a, x,));
+        assert_quote_eq!(list, expected);
+
+        let mut list: MetaList1 = parse_quote!(meta(a, b, c));
+        let tail = list.nested.split_off(1);
+        assert_quote_eq!(tail, quote::quote!(b, c));
+        let expected: MetaList1 = parse_quote!(meta(a));
+        assert_quote_eq!(list, expected);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut list: MetaList1 = parse_quote!(meta(a, b = "1", c, d = "2"));
+        list.nested.retain(|nested| match nested {
+            NestedMeta::Meta(meta) => !meta.path().is_ident("b"),
+            NestedMeta::Lit(_) => false,
+        });
+        list.nested
+            .retain(|nested| !nested.get_ident().unwrap().eq("c"));
+        let expected: MetaList1 = parse_quote!(meta(a, d = "2"));
+        assert_quote_eq!(list, expected);
+    }
+
+    #[test]
+    fn test_sort_dedup_nested_meta() {
+        let mut list: MetaList1 = parse_quote!(derive(Debug, Clone, Clone, Copy,));
+        list.nested
+            .sort_by_key(|nested| nested.get_ident().unwrap().to_string());
+        list.nested
+            .dedup_by_key(|nested| nested.get_ident().unwrap().to_string());
+        let expected: MetaList1 = parse_quote!(derive(Clone, Copy, Debug,));
+        assert_quote_eq!(list, expected);
+        let (index, _) = list
+            .nested
+            .find_position(|nested| nested.get_ident().unwrap() == "Debug")
+            .unwrap();
+        assert_eq!(index, 2);
+    }
+
+    #[test]
+    fn test_extend_unique_bounds() {
+        use quote::ToTokens;
+        use syn::{token::Plus, TypeParamBound};
+        let mut bounds: Punctuated<TypeParamBound, Plus> = parse_quote!(Clone + Send);
+        let key = |bound: &TypeParamBound| bound.to_token_stream().to_string();
+        let appended = bounds.extend_unique(
+            vec![
+                parse_quote!(Send),
+                parse_quote!('static),
+                parse_quote!(Sync),
+            ],
+            key,
+        );
+        assert_eq!(appended, 2);
+        assert_quote_eq!(bounds, quote::quote!(Clone + Send + 'static + Sync));
+    }
+
+    #[test]
+    fn test_partition_where_predicates() {
+        use syn::{token::Comma, WherePredicate};
+        let mut predicates: Punctuated<WherePredicate, Comma> =
+            parse_quote!(T: Clone, 'a: 'b, U: Send,);
+        let lifetimes = predicates.partition(|p| matches!(p, WherePredicate::Type(_)));
+        assert_quote_eq!(predicates, quote::quote!(T: Clone, U: Send,));
+        assert_quote_eq!(lifetimes, quote::quote!('a: 'b,));
+    }
 }
@@ -0,0 +1,427 @@
+use crate::attribute::{AttributeExt, IntoAttribute};
+use crate::error::combine;
+use crate::meta::{Meta1, MetaExt, MetaList1, NestedMeta, PunctuatedNestedMeta};
+use proc_macro2::Span;
+use syn::{parse_quote, spanned::Spanned, Attribute, Error, Ident, Lit, LitInt, Result};
+
+/// Primitive integer representation of `#[repr(u8)]` and others
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReprInt {
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+}
+
+impl ReprInt {
+    pub const ALL: [Self; 12] = [
+        Self::U8,
+        Self::U16,
+        Self::U32,
+        Self::U64,
+        Self::U128,
+        Self::Usize,
+        Self::I8,
+        Self::I16,
+        Self::I32,
+        Self::I64,
+        Self::I128,
+        Self::Isize,
+    ];
+
+    /// Returns the name of the primitive type
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::U8 => "u8",
+            Self::U16 => "u16",
+            Self::U32 => "u32",
+            Self::U64 => "u64",
+            Self::U128 => "u128",
+            Self::Usize => "usize",
+            Self::I8 => "i8",
+            Self::I16 => "i16",
+            Self::I32 => "i32",
+            Self::I64 => "i64",
+            Self::I128 => "i128",
+            Self::Isize => "isize",
+        }
+    }
+
+    /// Returns the integer representation named `ident`
+    pub fn from_ident(ident: &Ident) -> Option<Self> {
+        Self::ALL.iter().copied().find(|int| ident == int.name())
+    }
+}
+
+/// A hint of `#[repr(...)]`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReprHint {
+    C,
+    Rust,
+    Transparent,
+    Int(ReprInt),
+    /// `packed` is `Packed(1)`
+    Packed(u32),
+    Align(u32),
+}
+
+impl ReprHint {
+    fn to_nested_meta(self, span: Span) -> NestedMeta {
+        let ident = |name: &str| Ident::new(name, span);
+        let list = |name: &str, n: u32| {
+            let lit = LitInt::new(&n.to_string(), span);
+            Meta1::List(MetaList1 {
+                path: ident(name).into(),
+                paren_token: Default::default(),
+                nested: std::iter::once(NestedMeta::Lit(Lit::Int(lit))).collect(),
+            })
+        };
+        let meta = match self {
+            Self::C => Meta1::Path(ident("C").into()),
+            Self::Rust => Meta1::Path(ident("Rust").into()),
+            Self::Transparent => Meta1::Path(ident("transparent").into()),
+            Self::Int(int) => Meta1::Path(ident(int.name()).into()),
+            Self::Packed(1) => Meta1::Path(ident("packed").into()),
+            Self::Packed(n) => list("packed", n),
+            Self::Align(n) => list("align", n),
+        };
+        NestedMeta::Meta(meta)
+    }
+}
+
+/// Typed model of `#[repr(...)]` attributes
+///
+/// Hints keep the spans where they are parsed from to report errors.
+#[derive(Clone, Debug, Default)]
+pub struct Repr {
+    hints: Vec<(ReprHint, Span)>,
+}
+
+impl Repr {
+    /// Constructs an empty repr, which is the default representation
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a `repr(...)` meta
+    pub fn from_meta(meta: &Meta1) -> Result<Self> {
+        let list = meta.list()?;
+        if !list.path.is_ident("repr") {
+            return Err(Error::new_spanned(&list.path, "expected `repr`"));
+        }
+        let mut repr = Self::new();
+        for nested in &list.nested {
+            repr.hints.push((parse_hint(nested)?, nested.span()));
+        }
+        Ok(repr)
+    }
+
+    /// Returns hints in order
+    pub fn hints(&self) -> impl Iterator<Item = ReprHint> + '_ {
+        self.hints.iter().map(|(hint, _)| *hint)
+    }
+
+    /// Returns `true` if there is no hint
+    pub fn is_empty(&self) -> bool {
+        self.hints.is_empty()
+    }
+
+    /// Appends a hint unless it already exists
+    pub fn push(&mut self, hint: ReprHint) {
+        if !self.hints().any(|h| h == hint) {
+            self.hints.push((hint, Span::call_site()));
+        }
+    }
+
+    /// Removes hints matching the predicate
+    pub fn remove<F>(&mut self, mut f: F)
+    where
+        F: FnMut(ReprHint) -> bool,
+    {
+        self.hints.retain(|(hint, _)| !f(*hint));
+    }
+
+    /// Appends hints of `other` except the duplicated ones
+    pub fn merge(&mut self, other: Repr) {
+        for (hint, span) in other.hints {
+            if !self.hints().any(|h| h == hint) {
+                self.hints.push((hint, span));
+            }
+        }
+    }
+
+    pub fn is_c(&self) -> bool {
+        self.hints().any(|h| h == ReprHint::C)
+    }
+    pub fn is_rust(&self) -> bool {
+        self.hints().any(|h| h == ReprHint::Rust)
+    }
+    pub fn is_transparent(&self) -> bool {
+        self.hints().any(|h| h == ReprHint::Transparent)
+    }
+    /// Returns the primitive integer representation
+    pub fn int(&self) -> Option<ReprInt> {
+        self.hints().find_map(|h| match h {
+            ReprHint::Int(int) => Some(int),
+            _ => None,
+        })
+    }
+    /// Returns `N` of `packed(N)`; 1 for `packed`
+    pub fn packed(&self) -> Option<u32> {
+        self.hints().find_map(|h| match h {
+            ReprHint::Packed(n) => Some(n),
+            _ => None,
+        })
+    }
+    /// Returns `N` of `align(N)`
+    pub fn align(&self) -> Option<u32> {
+        self.hints().find_map(|h| match h {
+            ReprHint::Align(n) => Some(n),
+            _ => None,
+        })
+    }
+
+    /// Checks conflicting hints and returns all errors combined
+    ///
+    /// - `transparent` with any other hint
+    /// - `C` with `Rust`
+    /// - more than one primitive integer, `packed` or `align`
+    /// - `packed` with `align`
+    pub fn validate(&self) -> Result<()> {
+        let mut errors: Vec<Error> = Vec::new();
+        let mut seen: Vec<(ReprHint, Span)> = Vec::new();
+        for &(hint, span) in &self.hints {
+            let conflict = seen.iter().find(|(other, _)| conflicts(hint, *other));
+            if let Some((other, _)) = conflict {
+                errors.push(Error::new(
+                    span,
+                    format!(
+                        "conflicting representation hints: `{}` and `{}`",
+                        hint_name(hint),
+                        hint_name(*other)
+                    ),
+                ));
+            }
+            seen.push((hint, span));
+        }
+        combine(errors)
+    }
+
+    /// Constructs and returns `repr(...)` meta
+    pub fn to_meta(&self) -> Meta1 {
+        let nested: PunctuatedNestedMeta = self
+            .hints
+            .iter()
+            .map(|(hint, span)| hint.to_nested_meta(*span))
+            .collect();
+        Meta1::List(MetaList1 {
+            path: parse_quote!(repr),
+            paren_token: Default::default(),
+            nested,
+        })
+    }
+}
+
+impl IntoAttribute for Repr {
+    fn into_attribute(self) -> Attribute {
+        self.to_meta().into_attribute()
+    }
+}
+
+fn hint_name(hint: ReprHint) -> String {
+    match hint {
+        ReprHint::C => "C".to_owned(),
+        ReprHint::Rust => "Rust".to_owned(),
+        ReprHint::Transparent => "transparent".to_owned(),
+        ReprHint::Int(int) => int.name().to_owned(),
+        ReprHint::Packed(1) => "packed".to_owned(),
+        ReprHint::Packed(n) => format!("packed({})", n),
+        ReprHint::Align(n) => format!("align({})", n),
+    }
+}
+
+fn conflicts(a: ReprHint, b: ReprHint) -> bool {
+    use ReprHint::*;
+    matches!(
+        (a, b),
+        (Transparent, _)
+            | (_, Transparent)
+            | (C, Rust)
+            | (Rust, C)
+            | (Int(_), Int(_))
+            | (Packed(_), Packed(_))
+            | (Align(_), Align(_))
+            | (Packed(_), Align(_))
+            | (Align(_), Packed(_))
+    )
+}
+
+fn parse_hint(nested: &NestedMeta) -> Result<ReprHint> {
+    let meta = match nested {
+        NestedMeta::Meta(meta) => meta,
+        NestedMeta::Lit(lit) => return Err(Error::new_spanned(lit, "expected repr hint")),
+    };
+    let ident = meta
+        .path()
+        .get_ident()
+        .ok_or_else(|| Error::new_spanned(meta.path(), "unrecognized representation hint"))?;
+    let hint = match meta {
+        Meta1::Path(_) => match ident.to_string().as_str() {
+            "C" => ReprHint::C,
+            "Rust" => ReprHint::Rust,
+            "transparent" => ReprHint::Transparent,
+            "packed" => ReprHint::Packed(1),
+            _ => ReprHint::Int(
+                ReprInt::from_ident(ident)
+                    .ok_or_else(|| Error::new_spanned(ident, "unrecognized representation hint"))?,
+            ),
+        },
+        Meta1::List(list) if ident == "packed" || ident == "align" => {
+            let n = match list.nested.first() {
+                Some(NestedMeta::Lit(Lit::Int(lit))) if list.nested.len() == 1 => {
+                    let n: u32 = lit.base10_parse()?;
+                    if !n.is_power_of_two() {
+                        return Err(Error::new_spanned(lit, "not a power of two"));
+                    }
+                    n
+                }
+                _ => {
+                    return Err(Error::new_spanned(
+                        list,
+                        format!("`{}` expects a single integer literal", ident),
+                    ))
+                }
+            };
+            if ident == "packed" {
+                ReprHint::Packed(n)
+            } else {
+                ReprHint::Align(n)
+            }
+        }
+        _ => return Err(Error::new_spanned(meta, "unrecognized representation hint")),
+    };
+    Ok(hint)
+}
+
+/// Extension for `#[repr(...)]` of `Vec<[syn::Attribute]>`
+pub trait ReprAttrsExt {
+    /// Returns validated repr merged from every `#[repr(...)]`; `None` if there is no repr attribute
+    fn repr(&self) -> Result<Option<Repr>>;
+
+    /// Replaces every `#[repr(...)]` with a single attribute of `repr` at the position of the first one.
+    ///
+    /// The attributes are removed if `repr` is empty. `Err` if `repr` is not valid.
+    fn set_repr(&mut self, repr: Repr) -> Result<()>;
+}
+
+impl ReprAttrsExt for Vec<Attribute> {
+    fn repr(&self) -> Result<Option<Repr>> {
+        let mut merged: Option<Repr> = None;
+        for attr in self.iter().filter(|attr| attr.path().is_ident("repr")) {
+            let repr = Repr::from_meta(&attr.parse_meta()?)?;
+            match merged.as_mut() {
+                Some(merged) => merged.merge(repr),
+                None => merged = Some(repr),
+            }
+        }
+        if let Some(repr) = merged.as_ref() {
+            repr.validate()?;
+        }
+        Ok(merged)
+    }
+
+    fn set_repr(&mut self, repr: Repr) -> Result<()> {
+        repr.validate()?;
+        let first = self.iter().position(|attr| attr.path().is_ident("repr"));
+        self.retain(|attr| !attr.path().is_ident("repr"));
+        if !repr.is_empty() {
+            let index = first.unwrap_or(self.len());
+            self.insert(index, Attribute::from_meta(repr));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_quote_eq;
+
+    #[test]
+    fn test_from_meta() {
+        let meta: Meta1 = parse_quote!(repr(C, u8, align(8)));
+        let repr = Repr::from_meta(&meta).unwrap();
+        assert!(repr.is_c());
+        assert_eq!(repr.int(), Some(ReprInt::U8));
+        assert_eq!(repr.align(), Some(8));
+        assert_eq!(repr.packed(), None);
+        repr.validate().unwrap();
+        assert_quote_eq!(repr.to_meta(), meta);
+
+        let meta: Meta1 = parse_quote!(repr(packed, C));
+        let repr = Repr::from_meta(&meta).unwrap();
+        assert_eq!(repr.packed(), Some(1));
+        assert_quote_eq!(repr.to_meta(), meta);
+
+        assert!(Repr::from_meta(&parse_quote!(repr(align(3)))).is_err());
+        assert!(Repr::from_meta(&parse_quote!(repr(u7))).is_err());
+        assert!(Repr::from_meta(&parse_quote!(repr = "C")).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let invalid: Vec<Meta1> = vec![
+            parse_quote!(repr(transparent, C)),
+            parse_quote!(repr(C, Rust)),
+            parse_quote!(repr(u8, i32)),
+            parse_quote!(repr(packed(2), align(4))),
+        ];
+        for meta in &invalid {
+            assert!(Repr::from_meta(meta).unwrap().validate().is_err());
+        }
+        let err = Repr::from_meta(&parse_quote!(repr(transparent, C, u8)))
+            .unwrap()
+            .validate()
+            .unwrap_err();
+        assert_eq!(err.into_iter().count(), 2);
+    }
+
+    #[test]
+    fn test_attrs() {
+        let input: syn::DeriveInput = parse_quote! {
+            #[repr(C)]
+            #[doc = "doc"]
+            #[repr(u8)]
+            enum E { A }
+        };
+        let mut attrs = input.attrs;
+        let mut repr = attrs.repr().unwrap().unwrap();
+        assert!(repr.is_c());
+        assert_eq!(repr.int(), Some(ReprInt::U8));
+
+        repr.remove(|hint| hint == ReprHint::C);
+        repr.push(ReprHint::Align(4));
+        attrs.set_repr(repr).unwrap();
+        let expected = quote::quote! {
+            #[repr(u8, align(4))]
+            #[doc = "doc"]
+        };
+        assert_quote_eq!(quote::quote!(#(#attrs)*), expected);
+
+        let mut conflicting = Repr::new();
+        conflicting.push(ReprHint::Transparent);
+        conflicting.push(ReprHint::C);
+        assert!(attrs.set_repr(conflicting).is_err());
+
+        attrs.set_repr(Repr::new()).unwrap();
+        assert!(attrs.repr().unwrap().is_none());
+    }
+}
@@ -0,0 +1,606 @@
+use crate::error::combine;
+use crate::item::{ConstLike, FunctionLike, ItemLike, TypeLike};
+use quote::format_ident;
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    parse_quote, AngleBracketedGenericArguments, Attribute, Block, Error, Expr, FnArg,
+    GenericArgument, GenericParam, Generics, Ident, ImplItem, ImplItemConst, ImplItemFn,
+    ImplItemType, ItemImpl, ItemTrait, Lifetime, Pat, PatType, Path, Result, Signature, Type,
+    Visibility,
+};
+
+/// Returns the ident bound by a parameter of [named_sig]
+pub(crate) fn arg_ident(arg: &PatType) -> Ident {
+    match &*arg.pat {
+        Pat::Ident(pat) => pat.ident.clone(),
+        _ => unreachable!("named_sig binds every parameter to an ident"),
+    }
+}
+
+/// Returns `#[cfg]` and `#[cfg_attr]` attributes to be carried over to generated items
+pub(crate) fn cfg_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg") || attr.path().is_ident("cfg_attr"))
+        .cloned()
+        .collect()
+}
+
+/// Returns `true` if the parameter is bound to a plain name, like `x` or `mut x`
+fn is_named(arg: &PatType) -> bool {
+    matches!(&*arg.pat, Pat::Ident(pat) if pat.subpat.is_none())
+}
+
+/// Returns the signature with `_` and destructuring parameters renamed to `argN`.
+///
+/// Names already bound by other parameters are skipped, e.g. `arg0_1` is used next to `arg0`.
+pub(crate) fn named_sig(sig: &Signature) -> Signature {
+    let mut sig = sig.clone();
+    let mut taken: Vec<Ident> = sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(arg) if is_named(arg) => Some(arg_ident(arg)),
+            _ => None,
+        })
+        .collect();
+    for (i, input) in sig.inputs.iter_mut().enumerate() {
+        if let FnArg::Typed(arg) = input {
+            if !is_named(arg) {
+                let mut ident = format_ident!("arg{}", i);
+                let mut n = 0usize;
+                while taken.contains(&ident) {
+                    n += 1;
+                    ident = format_ident!("arg{}_{}", i, n);
+                }
+                *arg.pat = parse_quote!(#ident);
+                taken.push(ident);
+            }
+        }
+    }
+    sig
+}
+
+/// Converts a function-like item into an impl item with `block`
+pub(crate) fn impl_fn(f: &dyn FunctionLike, block: Block) -> ImplItemFn {
+    ImplItemFn {
+        attrs: cfg_attrs(f.attrs()),
+        vis: Visibility::Inherited,
+        defaultness: None,
+        sig: named_sig(f.sig()),
+        block,
+    }
+}
+
+/// Converts a const-like item into an impl item with `expr`
+pub(crate) fn impl_const(c: &dyn ConstLike, expr: Expr) -> ImplItemConst {
+    ImplItemConst {
+        attrs: cfg_attrs(c.attrs()),
+        vis: Visibility::Inherited,
+        defaultness: None,
+        const_token: *c.const_token(),
+        ident: c.ident().clone(),
+        generics: Default::default(),
+        colon_token: *c.colon_token(),
+        ty: c.ty().clone(),
+        eq_token: Default::default(),
+        expr,
+        semi_token: Default::default(),
+    }
+}
+
+/// Converts a type-like item into an impl item aliasing `ty`
+pub(crate) fn impl_type(t: &dyn TypeLike, ty: Type) -> ImplItemType {
+    ImplItemType {
+        attrs: cfg_attrs(t.attrs()),
+        vis: Visibility::Inherited,
+        defaultness: None,
+        type_token: Default::default(),
+        ident: t.ident().clone(),
+        generics: t.generics().clone(),
+        eq_token: Default::default(),
+        ty,
+        semi_token: Default::default(),
+    }
+}
+
+/// Replaces generic parameters of a trait with the arguments of an impl
+#[derive(Default)]
+struct Substitution {
+    types: Vec<(Ident, Type)>,
+    consts: Vec<(Ident, Expr)>,
+    lifetimes: Vec<(Lifetime, Lifetime)>,
+}
+
+impl Substitution {
+    /// Pairs parameters of `item` with `args`, falling back to defaults of omitted parameters
+    fn new(item: &ItemTrait, args: &AngleBracketedGenericArguments) -> Result<Self> {
+        let mismatch = || {
+            Error::new_spanned(
+                args,
+                format!(
+                    "trait `{}` takes {} generic arguments but {} were given",
+                    item.ident,
+                    item.generics.params.len(),
+                    args.args.len()
+                ),
+            )
+        };
+        if args.args.len() > item.generics.params.len() {
+            return Err(mismatch());
+        }
+        let mut substitution = Self::default();
+        let mut args = args.args.iter();
+        for param in &item.generics.params {
+            match (param, args.next()) {
+                (GenericParam::Lifetime(param), Some(GenericArgument::Lifetime(arg))) => {
+                    substitution
+                        .lifetimes
+                        .push((param.lifetime.clone(), arg.clone()));
+                }
+                (GenericParam::Type(param), Some(GenericArgument::Type(arg))) => {
+                    substitution.types.push((param.ident.clone(), arg.clone()));
+                }
+                (GenericParam::Type(param), None) => match &param.default {
+                    Some(default) => substitution
+                        .types
+                        .push((param.ident.clone(), default.clone())),
+                    None => return Err(mismatch()),
+                },
+                (GenericParam::Const(param), Some(GenericArgument::Const(arg))) => {
+                    substitution.consts.push((param.ident.clone(), arg.clone()));
+                }
+                // `N` in `Trait<N>` is parsed as a type
+                (GenericParam::Const(param), Some(GenericArgument::Type(Type::Path(arg))))
+                    if arg.qself.is_none() =>
+                {
+                    let path = &arg.path;
+                    substitution
+                        .consts
+                        .push((param.ident.clone(), parse_quote!(#path)));
+                }
+                (GenericParam::Const(param), None) => match &param.default {
+                    Some(default) => substitution
+                        .consts
+                        .push((param.ident.clone(), default.clone())),
+                    None => return Err(mismatch()),
+                },
+                (_, Some(arg)) => {
+                    return Err(Error::new_spanned(
+                        arg,
+                        format!(
+                            "expected argument of parameter `{}` of trait `{}`",
+                            param_name(param),
+                            item.ident
+                        ),
+                    ))
+                }
+                (GenericParam::Lifetime(_), None) => return Err(mismatch()),
+            }
+        }
+        Ok(substitution)
+    }
+
+    fn ty(&self, ident: &Ident) -> Option<&Type> {
+        self.types
+            .iter()
+            .find(|(param, _)| param == ident)
+            .map(|(_, ty)| ty)
+    }
+
+    /// Returns the const argument of `ident` in braces unless it is a literal, block or path
+    fn const_expr(&self, ident: &Ident) -> Option<Expr> {
+        let (_, expr) = self.consts.iter().find(|(param, _)| param == ident)?;
+        Some(match expr {
+            Expr::Lit(_) | Expr::Block(_) | Expr::Path(_) => expr.clone(),
+            expr => parse_quote!({ #expr }),
+        })
+    }
+}
+
+impl VisitMut for Substitution {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(path) = ty {
+            let first = path.path.segments.first();
+            let replacement = match first {
+                Some(first) if path.qself.is_none() && path.path.leading_colon.is_none() => {
+                    self.ty(&first.ident).cloned()
+                }
+                _ => None,
+            };
+            if let Some(replacement) = replacement {
+                let rest = path.path.segments.iter().skip(1);
+                *ty = if path.path.segments.len() == 1 {
+                    replacement
+                } else {
+                    // `T::Output` becomes `<Arg>::Output`
+                    parse_quote!(<#replacement>#(::#rest)*)
+                };
+                return;
+            }
+        }
+        visit_mut::visit_type_mut(self, ty);
+    }
+
+    fn visit_generic_argument_mut(&mut self, arg: &mut GenericArgument) {
+        if let GenericArgument::Type(Type::Path(path)) = arg {
+            let expr = match path.path.get_ident() {
+                Some(ident) if path.qself.is_none() => self.const_expr(ident),
+                _ => None,
+            };
+            if let Some(expr) = expr {
+                *arg = GenericArgument::Const(expr);
+                return;
+            }
+        }
+        visit_mut::visit_generic_argument_mut(self, arg);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if let Expr::Path(path) = expr {
+            let replacement = match path.path.get_ident() {
+                Some(ident) if path.qself.is_none() => self.const_expr(ident),
+                _ => None,
+            };
+            if let Some(replacement) = replacement {
+                *expr = replacement;
+                return;
+            }
+        }
+        visit_mut::visit_expr_mut(self, expr);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if let Some((_, arg)) = self.lifetimes.iter().find(|(param, _)| param == lifetime) {
+            *lifetime = arg.clone();
+        }
+    }
+}
+
+/// Returns `item` with its generic parameters replaced by `args` in its items and where clause.
+///
+/// Parameters shadowed by generics of methods are not taken into account.
+pub(crate) fn instantiate(
+    item: &ItemTrait,
+    args: Option<&AngleBracketedGenericArguments>,
+) -> Result<ItemTrait> {
+    let mut item = item.clone();
+    if let Some(args) = args {
+        let mut substitution = Substitution::new(&item, args)?;
+        for trait_item in &mut item.items {
+            substitution.visit_trait_item_mut(trait_item);
+        }
+        if let Some(where_clause) = &mut item.generics.where_clause {
+            substitution.visit_where_clause_mut(where_clause);
+        }
+    }
+    Ok(item)
+}
+
+/// Constructs an empty `impl Trait<..> for SelfTy`.
+///
+/// Without `trait_args`, parameters of the trait become parameters of the impl next to
+/// `generics`; `Err` if a parameter of `generics` has the name of a trait parameter, since
+/// merging them would implement the trait only for matching arguments. With `trait_args`,
+/// only `generics` are parameters of the impl and `item` is expected to be [instantiate]d.
+pub(crate) fn impl_header(
+    item: &ItemTrait,
+    self_ty: Type,
+    generics: &Generics,
+    trait_args: Option<&AngleBracketedGenericArguments>,
+) -> Result<ItemImpl> {
+    let mut impl_generics = match trait_args {
+        Some(_) => Generics {
+            where_clause: item.generics.where_clause.clone(),
+            ..Default::default()
+        },
+        None => item.generics.clone(),
+    };
+    for param in impl_generics.params.iter_mut() {
+        match param {
+            GenericParam::Type(param) => {
+                param.eq_token = None;
+                param.default = None;
+            }
+            GenericParam::Const(param) => {
+                param.eq_token = None;
+                param.default = None;
+            }
+            GenericParam::Lifetime(_) => {}
+        }
+    }
+    let mut errors = Vec::new();
+    for param in &generics.params {
+        let name = param_name(param);
+        if impl_generics.params.iter().any(|p| param_name(p) == name) {
+            errors.push(Error::new_spanned(
+                param,
+                format!(
+                    "generic parameter `{}` conflicts with a parameter of trait `{}`",
+                    name, item.ident
+                ),
+            ));
+        }
+    }
+    combine(errors)?;
+    impl_generics.params.extend(generics.params.iter().cloned());
+    if let Some(where_clause) = &generics.where_clause {
+        impl_generics
+            .make_where_clause()
+            .predicates
+            .extend(where_clause.predicates.iter().cloned());
+    }
+    let ident = &item.ident;
+    let path: Path = match trait_args {
+        Some(args) => parse_quote!(#ident #args),
+        None => {
+            let (_, ty_generics, _) = item.generics.split_for_impl();
+            parse_quote!(#ident #ty_generics)
+        }
+    };
+    Ok(ItemImpl {
+        attrs: cfg_attrs(&item.attrs),
+        defaultness: None,
+        unsafety: item.unsafety,
+        impl_token: Default::default(),
+        generics: impl_generics,
+        trait_: Some((None, path, Default::default())),
+        self_ty: Box::new(self_ty),
+        brace_token: Default::default(),
+        items: Vec::new(),
+    })
+}
+
+/// Returns the name of `param` as written, like `T` or `'a`
+fn param_name(param: &GenericParam) -> String {
+    match param {
+        GenericParam::Type(param) => param.ident.to_string(),
+        GenericParam::Const(param) => param.ident.to_string(),
+        GenericParam::Lifetime(param) => param.lifetime.to_string(),
+    }
+}
+
+/// Generator of `impl Trait for SelfTy` skeletons from [syn::ItemTrait]
+///
+/// Required methods get `todo!()` bodies and required consts get `todo!()` values
+/// unless given. Required associated types must be given. Provided items are
+/// only included when overridden.
+pub struct ImplSkeleton {
+    self_ty: Type,
+    generics: Generics,
+    trait_args: Option<AngleBracketedGenericArguments>,
+    bodies: Vec<(Ident, Block)>,
+    types: Vec<(Ident, Type)>,
+    consts: Vec<(Ident, Expr)>,
+}
+
+impl ImplSkeleton {
+    pub fn new(self_ty: Type) -> Self {
+        Self {
+            self_ty,
+            generics: Default::default(),
+            trait_args: None,
+            bodies: Vec::new(),
+            types: Vec::new(),
+            consts: Vec::new(),
+        }
+    }
+
+    /// Sets generics of the target type, e.g. `<T: Clone>` for `A<T>`
+    pub fn generics(mut self, generics: Generics) -> Self {
+        self.generics = generics;
+        self
+    }
+
+    /// Sets generic arguments of the trait, e.g. `<T>` for `impl<T> Trait<T> for A<T>`.
+    ///
+    /// Parameters of the trait become parameters of the impl unless given.
+    pub fn trait_args(mut self, args: AngleBracketedGenericArguments) -> Self {
+        self.trait_args = Some(args);
+        self
+    }
+
+    /// Sets the body of the method `name`
+    pub fn body(mut self, name: Ident, block: Block) -> Self {
+        self.bodies.push((name, block));
+        self
+    }
+
+    /// Sets the associated type `name`
+    pub fn assoc_type(mut self, name: Ident, ty: Type) -> Self {
+        self.types.push((name, ty));
+        self
+    }
+
+    /// Sets the value of the associated const `name`
+    pub fn assoc_const(mut self, name: Ident, expr: Expr) -> Self {
+        self.consts.push((name, expr));
+        self
+    }
+
+    /// Generates the impl of `item`
+    pub fn build(&self, item: &ItemTrait) -> Result<ItemImpl> {
+        fn given<'a, T>(given: &'a [(Ident, T)], ident: &Ident) -> Option<&'a T> {
+            given.iter().find(|(name, _)| name == ident).map(|(_, v)| v)
+        }
+
+        let mut errors = Vec::new();
+        let trait_args = self.trait_args.as_ref();
+        let item = &instantiate(item, trait_args)?;
+        let mut generated = impl_header(item, self.self_ty.clone(), &self.generics, trait_args)?;
+        for trait_item in &item.items {
+            if let Ok(f) = trait_item.function_or_method() {
+                let block = match (given(&self.bodies, &f.sig().ident), f.block()) {
+                    (Some(block), _) => block.clone(),
+                    (None, None) => parse_quote!({ todo!() }),
+                    (None, Some(_)) => continue,
+                };
+                generated.items.push(ImplItem::Fn(impl_fn(f, block)));
+            } else if let Ok(c) = trait_item.constant() {
+                let expr = match (given(&self.consts, c.ident()), c.expr()) {
+                    (Some(expr), _) => expr.clone(),
+                    (None, None) => parse_quote!(todo!()),
+                    (None, Some(_)) => continue,
+                };
+                generated.items.push(ImplItem::Const(impl_const(c, expr)));
+            } else if let Ok(t) = trait_item.type_alias() {
+                let ty = match (given(&self.types, t.ident()), t.ty()) {
+                    (Some(ty), _) => ty.clone(),
+                    (None, None) => {
+                        errors.push(Error::new_spanned(
+                            t.ident(),
+                            format!("missing type for associated type `{}`", t.ident()),
+                        ));
+                        continue;
+                    }
+                    (None, Some(_)) => continue,
+                };
+                generated.items.push(ImplItem::Type(impl_type(t, ty)));
+            }
+        }
+        let names = self
+            .bodies
+            .iter()
+            .map(|(name, _)| name)
+            .chain(self.types.iter().map(|(name, _)| name))
+            .chain(self.consts.iter().map(|(name, _)| name));
+        for name in names {
+            let found = generated.items.iter().any(|item| match item {
+                ImplItem::Fn(f) => f.sig.ident == *name,
+                ImplItem::Const(c) => c.ident == *name,
+                ImplItem::Type(t) => t.ident == *name,
+                _ => false,
+            });
+            if !found {
+                errors.push(Error::new_spanned(
+                    name,
+                    format!("`{}` is not a member of trait `{}`", name, item.ident),
+                ));
+            }
+        }
+        combine(errors)?;
+        Ok(generated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_quote_eq;
+
+    #[test]
+    fn test_build() {
+        let item: ItemTrait = parse_quote! {
+            #[cfg(feature = "a")]
+            #[doc = "trait"]
+            pub unsafe trait A<T = u8>: Clone {
+                type Output;
+                const N: usize;
+                const M: usize = 0;
+                #[cfg(test)]
+                #[doc = "f"]
+                fn f(&self, _: T, x: u8) -> Self::Output;
+                fn g(&self) {}
+                fn h(&self) {}
+                fn k(_: u8, arg0: u8);
+            }
+        };
+        let generated = ImplSkeleton::new(parse_quote!(B<U>))
+            .generics(parse_quote!(<U: Clone>))
+            .assoc_type(parse_quote!(Output), parse_quote!(()))
+            .body(parse_quote!(h), parse_quote!({ println!() }))
+            .build(&item)
+            .unwrap();
+        let expected: ItemImpl = parse_quote! {
+            #[cfg(feature = "a")]
+            unsafe impl<T, U: Clone> A<T> for B<U> {
+                type Output = ();
+                const N: usize = todo!();
+                #[cfg(test)]
+                fn f(&self, arg1: T, x: u8) -> Self::Output { todo!() }
+                fn h(&self) { println!() }
+                fn k(arg0_1: u8, arg0: u8) { todo!() }
+            }
+        };
+        assert_quote_eq!(generated, expected);
+    }
+
+    #[test]
+    fn test_build_trait_args() {
+        let item: ItemTrait = parse_quote! {
+            trait Convert<'a, T, const N: usize, U = ()> where T: Clone {
+                fn convert(&self, t: &'a T, u: U) -> [T::Output; N];
+            }
+        };
+        let generated = ImplSkeleton::new(parse_quote!(Wrapper<T>))
+            .generics(parse_quote!(<T: Clone>))
+            .trait_args(parse_quote!(<'static, T, 3>))
+            .build(&item)
+            .unwrap();
+        let expected: ItemImpl = parse_quote! {
+            impl<T: Clone> Convert<'static, T, 3> for Wrapper<T> where T: Clone {
+                fn convert(&self, t: &'static T, u: ()) -> [<T>::Output; 3] { todo!() }
+            }
+        };
+        assert_quote_eq!(generated, expected);
+
+        let generated = ImplSkeleton::new(parse_quote!(B))
+            .trait_args(parse_quote!(<'a, u8, { 1 + 2 }, u16>))
+            .build(&item)
+            .unwrap();
+        let expected: ItemImpl = parse_quote! {
+            impl Convert<'a, u8, { 1 + 2 }, u16> for B where u8: Clone {
+                fn convert(&self, t: &'a u8, u: u16) -> [<u8>::Output; { 1 + 2 }] { todo!() }
+            }
+        };
+        assert_quote_eq!(generated, expected);
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let item: ItemTrait = parse_quote! {
+            trait A {
+                type Output;
+            }
+        };
+        let err = ImplSkeleton::new(parse_quote!(B))
+            .body(parse_quote!(unknown), parse_quote!({}))
+            .build(&item)
+            .err()
+            .unwrap();
+        let messages: Vec<_> = err.into_iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "missing type for associated type `Output`",
+                "`unknown` is not a member of trait `A`",
+            ]
+        );
+
+        let item: ItemTrait = parse_quote! {
+            trait Convert<'a, T> {}
+        };
+        let err = ImplSkeleton::new(parse_quote!(B))
+            .trait_args(parse_quote!(<'static, u8, u16>))
+            .build(&item)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "trait `Convert` takes 2 generic arguments but 3 were given"
+        );
+        let err = ImplSkeleton::new(parse_quote!(Wrapper<'a, T, U>))
+            .generics(parse_quote!(<'a, T, U>))
+            .build(&item)
+            .err()
+            .unwrap();
+        let messages: Vec<_> = err.into_iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "generic parameter `'a` conflicts with a parameter of trait `Convert`",
+                "generic parameter `T` conflicts with a parameter of trait `Convert`",
+            ]
+        );
+    }
+}
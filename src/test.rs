@@ -7,3 +7,9 @@ macro_rules! assert_quote_eq {
         assert_eq!(quote! { #v1 }.to_string(), quote! { #v2 }.to_string());
     }};
 }
+
+/// Returns the attributes of `item` to test attribute lists with `parse_quote!`
+#[cfg(all(any(feature = "derive", feature = "full"), feature = "parsing"))]
+pub(crate) fn attrs(item: syn::DeriveInput) -> Vec<syn::Attribute> {
+    item.attrs
+}
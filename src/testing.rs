@@ -0,0 +1,265 @@
+use crate::attribute::AttributeExt;
+use crate::builtin::lit_str;
+use crate::item::{is_cfg_test, FunctionLike};
+use crate::meta::{Meta1, NestedMeta};
+use syn::{parse_quote, Attribute, Error, ItemMod, LitStr, Result};
+
+/// `#[should_panic]`, `#[should_panic = ".."]` or `#[should_panic(expected = "..")]`
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+pub struct ShouldPanic {
+    pub expected: Option<LitStr>,
+}
+
+/// Test attributes of a function
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+pub struct TestAttrs {
+    /// `#[test]`
+    pub test: bool,
+    /// `#[bench]`
+    pub bench: bool,
+    /// `#[ignore]` with its optional reason
+    pub ignore: Option<Option<LitStr>>,
+    pub should_panic: Option<ShouldPanic>,
+}
+
+const TEST_ATTRS: [&str; 4] = ["test", "bench", "ignore", "should_panic"];
+
+fn is_test_attr(attr: &Attribute) -> bool {
+    TEST_ATTRS.iter().any(|name| attr.path().is_ident(name))
+}
+
+impl ShouldPanic {
+    /// Parses `should_panic` meta in any of its forms
+    pub fn from_meta(meta: &Meta1) -> Result<Self> {
+        let expected = match meta {
+            Meta1::Path(_) => None,
+            Meta1::NameValue(nv) => Some(lit_str(&nv.value)?),
+            Meta1::List(list) => match list.nested.first() {
+                Some(NestedMeta::Meta(Meta1::NameValue(nv)))
+                    if list.nested.len() == 1 && nv.path.is_ident("expected") =>
+                {
+                    Some(lit_str(&nv.value)?)
+                }
+                _ => {
+                    return Err(Error::new_spanned(
+                        meta,
+                        "expected `should_panic(expected = \"...\")`",
+                    ))
+                }
+            },
+        };
+        Ok(Self { expected })
+    }
+
+    /// Constructs and returns `should_panic` meta
+    pub fn to_meta(&self) -> Meta1 {
+        match &self.expected {
+            None => parse_quote!(should_panic),
+            Some(expected) => parse_quote!(should_panic(expected = #expected)),
+        }
+    }
+}
+
+impl TestAttrs {
+    /// Parses `#[test]`, `#[bench]`, `#[ignore]` and `#[should_panic]` of `attrs`.
+    ///
+    /// Other attributes are skipped.
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
+        let mut test_attrs = Self::default();
+        for attr in attrs.iter().filter(|attr| is_test_attr(attr)) {
+            let meta = attr.parse_meta()?;
+            let duplicated = || Error::new_spanned(attr, "duplicated test attribute");
+            let name = meta.path().get_ident().map(|ident| ident.to_string());
+            match (name.as_deref(), &meta) {
+                (Some("test"), Meta1::Path(_)) if !test_attrs.test => test_attrs.test = true,
+                (Some("bench"), Meta1::Path(_)) if !test_attrs.bench => test_attrs.bench = true,
+                (Some("ignore"), _) if test_attrs.ignore.is_some() => return Err(duplicated()),
+                (Some("ignore"), Meta1::Path(_)) => test_attrs.ignore = Some(None),
+                (Some("ignore"), Meta1::NameValue(nv)) => {
+                    test_attrs.ignore = Some(Some(lit_str(&nv.value)?))
+                }
+                (Some("should_panic"), _) if test_attrs.should_panic.is_some() => {
+                    return Err(duplicated())
+                }
+                (Some("should_panic"), meta) => {
+                    test_attrs.should_panic = Some(ShouldPanic::from_meta(meta)?)
+                }
+                (Some("test"), Meta1::Path(_)) | (Some("bench"), Meta1::Path(_)) => {
+                    return Err(duplicated())
+                }
+                _ => return Err(Error::new_spanned(attr, "malformed test attribute")),
+            }
+        }
+        Ok(test_attrs)
+    }
+
+    /// Returns `true` if the function is a test or a benchmark
+    pub fn is_test(&self) -> bool {
+        self.test || self.bench
+    }
+
+    /// Constructs and returns the attributes in the order of
+    /// `#[test]`, `#[bench]`, `#[ignore]` and `#[should_panic]`
+    pub fn to_attrs(&self) -> Vec<Attribute> {
+        let mut attrs = Vec::new();
+        if self.test {
+            attrs.push(parse_quote!(#[test]));
+        }
+        if self.bench {
+            attrs.push(parse_quote!(#[bench]));
+        }
+        match &self.ignore {
+            Some(None) => attrs.push(parse_quote!(#[ignore])),
+            Some(Some(reason)) => attrs.push(parse_quote!(#[ignore = #reason])),
+            None => {}
+        }
+        if let Some(should_panic) = &self.should_panic {
+            let meta = should_panic.to_meta();
+            attrs.push(parse_quote!(#[#meta]));
+        }
+        attrs
+    }
+
+    /// Replaces test attributes of `attrs` with these attributes.
+    ///
+    /// New attributes are inserted where the first replaced one was, or at the end.
+    pub fn apply_to(&self, attrs: &mut Vec<Attribute>) {
+        let index = attrs.iter().position(is_test_attr).unwrap_or(attrs.len());
+        attrs.retain(|attr| !is_test_attr(attr));
+        let index = index.min(attrs.len());
+        attrs.splice(index..index, self.to_attrs());
+    }
+}
+
+/// Test attributes of functions using `crate::ext::FunctionLike`
+pub trait TestFnExt: FunctionLike {
+    /// Returns parsed test attributes of the function
+    fn test_attrs(&self) -> Result<TestAttrs> {
+        TestAttrs::from_attrs(self.attrs())
+    }
+
+    /// Returns `true` if the function has `#[test]` or `#[bench]`
+    fn is_test_fn(&self) -> Result<bool> {
+        Ok(self.test_attrs()?.is_test())
+    }
+
+    /// Copies test attributes of the function onto `target`, e.g. a generated test case
+    fn copy_test_attrs_to<F>(&self, target: &mut F) -> Result<()>
+    where
+        F: FunctionLike + ?Sized,
+    {
+        self.test_attrs()?.apply_to(target.attrs_mut());
+        Ok(())
+    }
+}
+
+impl<T: FunctionLike + ?Sized> TestFnExt for T {}
+
+/// Test module detection of [syn::ItemMod]
+pub trait TestModExt {
+    /// Returns `true` if the module is only compiled for tests, like `#[cfg(test)]` or `#[cfg(all(test, unix))]`
+    fn is_test_mod(&self) -> bool;
+}
+
+impl TestModExt for ItemMod {
+    fn is_test_mod(&self) -> bool {
+        is_cfg_test(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_quote_eq;
+    use syn::ItemFn;
+
+    #[test]
+    fn test_test_attrs() {
+        let item: ItemFn = parse_quote! {
+            #[doc = "case"]
+            #[test]
+            #[ignore = "slow"]
+            #[should_panic = "boom"]
+            fn f() {}
+        };
+        let attrs = item.test_attrs().unwrap();
+        assert!(attrs.test && !attrs.bench);
+        assert_eq!(attrs.ignore.unwrap().unwrap().value(), "slow");
+        assert_eq!(
+            attrs.should_panic.unwrap().expected.unwrap().value(),
+            "boom"
+        );
+        assert!(item.is_test_fn().unwrap());
+
+        let plain: ItemFn = parse_quote! { fn g() {} };
+        assert!(!plain.is_test_fn().unwrap());
+
+        let malformed: Vec<ItemFn> = vec![
+            parse_quote! { #[test] #[test] fn f() {} },
+            parse_quote! { #[test(x)] fn f() {} },
+            parse_quote! { #[should_panic(reason = "x")] fn f() {} },
+        ];
+        for item in &malformed {
+            assert!(item.test_attrs().is_err());
+        }
+    }
+
+    #[test]
+    fn test_copy_test_attrs() {
+        let template: ItemFn = parse_quote! {
+            #[test]
+            #[should_panic(expected = "boom")]
+            fn template() {}
+        };
+        let mut generated: ItemFn = parse_quote! {
+            #[inline]
+            #[ignore]
+            #[allow(unused)]
+            fn case_1() {}
+        };
+        template.copy_test_attrs_to(&mut generated).unwrap();
+        let attrs = &generated.attrs;
+        let expected = quote::quote! {
+            #[inline]
+            #[test]
+            #[should_panic(expected = "boom")]
+            #[allow(unused)]
+        };
+        assert_quote_eq!(quote::quote!(#(#attrs)*), expected);
+    }
+
+    #[test]
+    fn test_is_test_mod() {
+        let module: ItemMod = parse_quote! {
+            #[cfg(test)]
+            mod tests {}
+        };
+        assert!(module.is_test_mod());
+        let module: ItemMod = parse_quote!(
+            mod tests {}
+        );
+        assert!(!module.is_test_mod());
+        let module: ItemMod = parse_quote! {
+            #[cfg(all(test, feature = "std"))]
+            mod tests {}
+        };
+        assert!(module.is_test_mod());
+        let module: ItemMod = parse_quote! {
+            #[cfg(any(test, all(test, unix)))]
+            mod tests {}
+        };
+        assert!(module.is_test_mod());
+        let module: ItemMod = parse_quote! {
+            #[cfg(any(test, doc))]
+            mod tests {}
+        };
+        assert!(!module.is_test_mod());
+        let module: ItemMod = parse_quote! {
+            #[cfg(not(test))]
+            mod tests {}
+        };
+        assert!(!module.is_test_mod());
+    }
+}
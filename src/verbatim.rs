@@ -0,0 +1,174 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{ToTokens, TokenStreamExt};
+use syn::parse::{Parse, ParseStream};
+use syn::{Attribute, Ident, Result, Visibility};
+
+/// Best-effort view of verbatim item tokens, e.g. `Item::Verbatim`
+///
+/// syn emits verbatim tokens for syntax it doesn't model, like `unsafe extern "C" { ... }`
+/// or `static A = 0;`. Leading attributes and visibility are recovered; the rest is kept opaque.
+#[derive(Clone)]
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+pub struct VerbatimItem {
+    pub attrs: Vec<Attribute>,
+    pub vis: Visibility,
+    /// Tokens after the visibility
+    pub rest: TokenStream,
+}
+
+/// Keywords followed by the name of the item
+const NAMED_KEYWORDS: &[&str] = &[
+    "const", "crate", "enum", "fn", "macro", "mod", "static", "struct", "trait", "type", "union",
+];
+
+/// Keywords which can't be the name of an item
+const SKIPPED_KEYWORDS: &[&str] = &[
+    "async", "auto", "const", "default", "extern", "fn", "impl", "mut", "safe", "unsafe", "_",
+];
+
+impl Parse for VerbatimItem {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            attrs: input.call(Attribute::parse_outer)?,
+            vis: input.parse()?,
+            rest: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for VerbatimItem {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.append_all(&self.attrs);
+        self.vis.to_tokens(tokens);
+        self.rest.to_tokens(tokens);
+    }
+}
+
+impl VerbatimItem {
+    /// Returns the name of the item if found before its body.
+    ///
+    /// `None` for nameless items like `impl` or `extern` blocks.
+    pub fn ident(&self) -> Option<Ident> {
+        let mut named = false;
+        for token in self.rest.clone() {
+            let ident = match token {
+                TokenTree::Ident(ident) => ident,
+                TokenTree::Literal(_) => continue,
+                _ => return None,
+            };
+            let name = ident.to_string();
+            if name == "impl" {
+                return None;
+            }
+            if named && !SKIPPED_KEYWORDS.contains(&name.as_str()) {
+                return Some(ident);
+            }
+            if NAMED_KEYWORDS.contains(&name.as_str()) {
+                named = true;
+            } else if !SKIPPED_KEYWORDS.contains(&name.as_str()) {
+                named = false;
+            }
+        }
+        None
+    }
+}
+
+/// Re-parsing of verbatim `syn::*Item`
+pub trait VerbatimExt {
+    /// Returns the verbatim tokens if the item is verbatim
+    fn verbatim_tokens(&self) -> Option<&TokenStream>;
+    /// Returns mutable verbatim tokens if the item is verbatim
+    fn verbatim_tokens_mut(&mut self) -> Option<&mut TokenStream>;
+
+    /// Re-parses verbatim tokens. `Ok(None)` if the item is not verbatim.
+    fn parse_verbatim(&self) -> Result<Option<VerbatimItem>> {
+        self.verbatim_tokens()
+            .map(|tokens| syn::parse2(tokens.clone()))
+            .transpose()
+    }
+
+    /// Re-parses verbatim tokens, calls `f` with them and writes them back.
+    ///
+    /// `Ok(None)` without calling `f` if the item is not verbatim.
+    fn try_verbatim_mut<F, R>(&mut self, f: F) -> Result<Option<R>>
+    where
+        F: FnOnce(&mut VerbatimItem) -> Result<R>,
+    {
+        let tokens = match self.verbatim_tokens_mut() {
+            Some(tokens) => tokens,
+            None => return Ok(None),
+        };
+        let mut verbatim: VerbatimItem = syn::parse2(tokens.clone())?;
+        let result = f(&mut verbatim)?;
+        *tokens = verbatim.into_token_stream();
+        Ok(Some(result))
+    }
+}
+
+macro_rules! impl_verbatim_ext {
+    ($($ty:ident),*) => {
+        $(
+            impl VerbatimExt for syn::$ty {
+                fn verbatim_tokens(&self) -> Option<&TokenStream> {
+                    match self {
+                        syn::$ty::Verbatim(tokens) => Some(tokens),
+                        _ => None,
+                    }
+                }
+                fn verbatim_tokens_mut(&mut self) -> Option<&mut TokenStream> {
+                    match self {
+                        syn::$ty::Verbatim(tokens) => Some(tokens),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_verbatim_ext!(Item, ImplItem, TraitItem, ForeignItem);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_quote_eq;
+    use quote::quote;
+    use syn::Item;
+
+    #[test]
+    fn test_parse_verbatim() {
+        let cases = [
+            (quote! { #[a] pub static A = 0; }, Some("A")),
+            (quote! { #[a] pub(crate) const unsafe fn f() {} }, Some("f")),
+            (quote! { #[a] unsafe extern "C" { fn f(); } }, None),
+            (quote! { #[a] impl<T> !Send for A<T> {} }, None),
+            (quote! { #[a] pub safe static mut B: u8; }, Some("B")),
+        ];
+        for (tokens, ident) in cases.iter() {
+            let item = Item::Verbatim(tokens.clone());
+            let verbatim = item.parse_verbatim().unwrap().unwrap();
+            assert_eq!(verbatim.attrs.len(), 1);
+            assert_eq!(
+                verbatim.ident().map(|ident| ident.to_string()).as_deref(),
+                *ident
+            );
+            assert_quote_eq!(verbatim, tokens);
+        }
+
+        let item: Item = syn::parse_quote! { struct A; };
+        assert!(item.parse_verbatim().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_try_verbatim_mut() {
+        let mut item = Item::Verbatim(quote!(#[a] pub static A = 0;));
+        item.try_verbatim_mut(|verbatim| {
+            verbatim.attrs.push(syn::parse_quote!(#[b]));
+            verbatim.vis = Visibility::Inherited;
+            Ok(())
+        })
+        .unwrap()
+        .unwrap();
+        assert_quote_eq!(item, quote!(#[a] #[b] static A = 0;));
+    }
+}